@@ -2,12 +2,13 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use std::time::Duration;
 
 use avro_rs::{
+    codec::Codec,
     schema::Schema,
     types::{Record, ToAvro, Value},
     Reader, Writer,
 };
 
-static RAW_SMALL_SCHEMA: &'static str = r#"
+static RAW_SMALL_SCHEMA: &str = r#"
 {
   "namespace": "test",
   "type": "record",
@@ -23,7 +24,7 @@ static RAW_SMALL_SCHEMA: &'static str = r#"
 }
 "#;
 
-static RAW_BIG_SCHEMA: &'static str = r#"
+static RAW_BIG_SCHEMA: &str = r#"
 {
   "namespace": "my.example",
   "type": "record",
@@ -88,7 +89,7 @@ static RAW_BIG_SCHEMA: &'static str = r#"
 }
 "#;
 
-static RAW_ADDRESS_SCHEMA: &'static str = r#"
+static RAW_ADDRESS_SCHEMA: &str = r#"
 {
   "fields": [
     {
@@ -156,11 +157,11 @@ fn make_big_record() -> (Schema, Value) {
 }
 
 fn make_records(record: Value, count: usize) -> Vec<Value> {
-    std::iter::repeat(record).take(count).collect()
+    std::iter::repeat_n(record, count).collect()
 }
 
 fn write(schema: &Schema, records: &[Value]) -> Vec<u8> {
-    let mut writer = Writer::new(&schema, Vec::new());
+    let mut writer = Writer::new(schema, Vec::new());
     writer.extend_from_slice(records).unwrap();
     writer.into_inner()
 }
@@ -209,14 +210,79 @@ fn bench_from_file(c: &mut Criterion, file_path: &str, name: &'static str) {
     c.bench_function(name, |b| b.iter(|| read_schemaless(&bytes)));
 }
 
+// Unlike `bench_from_file`, which reads the whole file into a `Vec<u8>`
+// up front, this streams directly from a `BufReader` over the file handle,
+// decoding one container block at a time. It measures the constant-memory
+// path recommended for multi-gigabyte `.avro` files.
+fn bench_from_file_streaming(c: &mut Criterion, file_path: &str, name: &'static str) {
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let file = std::fs::File::open(file_path).unwrap();
+            let buffered = std::io::BufReader::new(file);
+            let reader = Reader::new(buffered).unwrap();
+            for record in reader {
+                let _ = record.unwrap();
+            }
+        })
+    });
+}
+
+fn write_with_codec(schema: &Schema, records: &[Value], codec: Codec) -> Vec<u8> {
+    let mut writer = Writer::with_codec(schema, Vec::new(), codec);
+    writer.extend_from_slice(records).unwrap();
+    writer.into_inner()
+}
+
+fn bench_write_codec(
+    c: &mut Criterion,
+    make_record: impl Fn() -> (Schema, Value),
+    n_records: usize,
+    codec: Codec,
+    name: &'static str,
+) {
+    let (schema, record) = make_record();
+    let records = make_records(record, n_records);
+    c.bench_function(name, |b| b.iter(|| write_with_codec(&schema, &records, codec)));
+}
+
+fn bench_read_codec(
+    c: &mut Criterion,
+    make_record: impl Fn() -> (Schema, Value),
+    n_records: usize,
+    codec: Codec,
+    name: &'static str,
+) {
+    let (schema, record) = make_record();
+    let records = make_records(record, n_records);
+    let bytes = write_with_codec(&schema, &records, codec);
+    c.bench_function(name, |b| b.iter(|| read(&schema, &bytes)));
+}
+
+fn bench_write_streaming(
+    c: &mut Criterion,
+    make_record: impl Fn() -> (Schema, Value),
+    n_records: usize,
+    name: &'static str,
+) {
+    let (schema, record) = make_record();
+    let records = make_records(record, n_records);
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let mut writer = Writer::new(&schema, std::io::BufWriter::new(Vec::new()));
+            writer.extend_from_slice(&records).unwrap();
+            writer.into_inner().into_inner().unwrap()
+        })
+    });
+}
+
 fn bench_small_schema_write_1_record(c: &mut Criterion) {
-    bench_write(c, &make_small_record, 1, "small schema, write 1 record");
+    bench_write(c, make_small_record, 1, "small schema, write 1 record");
 }
 
 fn bench_small_schema_write_100_record(c: &mut Criterion) {
     bench_write(
         c,
-        &make_small_record,
+        make_small_record,
         100,
         "small schema, write 100 records",
     );
@@ -225,55 +291,55 @@ fn bench_small_schema_write_100_record(c: &mut Criterion) {
 fn bench_small_schema_write_10000_record(c: &mut Criterion) {
     bench_write(
         c,
-        &make_small_record,
+        make_small_record,
         10000,
         "small schema, write 10k records",
     );
 }
 
 fn bench_small_schema_read_1_record(c: &mut Criterion) {
-    bench_read(c, &make_small_record, 1, "small schema, read 1 record");
+    bench_read(c, make_small_record, 1, "small schema, read 1 record");
 }
 
 fn bench_small_schema_read_100_record(c: &mut Criterion) {
-    bench_read(c, &make_small_record, 100, "small schema, read 100 records");
+    bench_read(c, make_small_record, 100, "small schema, read 100 records");
 }
 
 fn bench_small_schema_read_10000_record(c: &mut Criterion) {
     bench_read(
         c,
-        &make_small_record,
+        make_small_record,
         10000,
         "small schema, read 10k records",
     );
 }
 
 fn bench_big_schema_write_1_record(c: &mut Criterion) {
-    bench_write(c, &make_big_record, 1, "big schema, write 1 record");
+    bench_write(c, make_big_record, 1, "big schema, write 1 record");
 }
 
 fn bench_big_schema_write_100_record(c: &mut Criterion) {
-    bench_write(c, &make_big_record, 100, "big schema, write 100 records");
+    bench_write(c, make_big_record, 100, "big schema, write 100 records");
 }
 
 fn bench_big_schema_write_10000_record(c: &mut Criterion) {
-    bench_write(c, &make_big_record, 10000, "big schema, write 10k records");
+    bench_write(c, make_big_record, 10000, "big schema, write 10k records");
 }
 
 fn bench_big_schema_read_1_record(c: &mut Criterion) {
-    bench_read(c, &make_big_record, 1, "big schema, read 1 record");
+    bench_read(c, make_big_record, 1, "big schema, read 1 record");
 }
 
 fn bench_big_schema_read_100_record(c: &mut Criterion) {
-    bench_read(c, &make_big_record, 100, "big schema, read 100 records");
+    bench_read(c, make_big_record, 100, "big schema, read 100 records");
 }
 
 fn bench_big_schema_read_10000_record(c: &mut Criterion) {
-    bench_read(c, &make_big_record, 10000, "big schema, read 10k records");
+    bench_read(c, make_big_record, 10000, "big schema, read 10k records");
 }
 
 fn bench_big_schema_read_100000_record(c: &mut Criterion) {
-    bench_read(c, &make_big_record, 100000, "big schema, read 100k records");
+    bench_read(c, make_big_record, 100000, "big schema, read 100k records");
 }
 
 // This benchmark reads from the `benches/quickstop-null.avro` file, which was pulled from
@@ -284,6 +350,99 @@ fn bench_file_quickstop_null(c: &mut Criterion) {
     bench_from_file(c, "benches/quickstop-null.avro", "quickstop null file");
 }
 
+fn bench_file_quickstop_null_streaming(c: &mut Criterion) {
+    bench_from_file_streaming(c, "benches/quickstop-null.avro", "quickstop null file, streaming");
+}
+
+fn bench_big_schema_write_10000_record_streaming(c: &mut Criterion) {
+    bench_write_streaming(
+        c,
+        make_big_record,
+        10000,
+        "big schema, write 10k records, streaming",
+    );
+}
+
+fn bench_big_schema_write_10000_record_null(c: &mut Criterion) {
+    bench_write_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Null,
+        "big schema, write 10k records, null codec",
+    );
+}
+
+fn bench_big_schema_write_10000_record_deflate(c: &mut Criterion) {
+    bench_write_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Deflate,
+        "big schema, write 10k records, deflate codec",
+    );
+}
+
+fn bench_big_schema_write_10000_record_snappy(c: &mut Criterion) {
+    bench_write_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Snappy,
+        "big schema, write 10k records, snappy codec",
+    );
+}
+
+fn bench_big_schema_write_10000_record_zstd(c: &mut Criterion) {
+    bench_write_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Zstd,
+        "big schema, write 10k records, zstd codec",
+    );
+}
+
+fn bench_big_schema_read_10000_record_null(c: &mut Criterion) {
+    bench_read_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Null,
+        "big schema, read 10k records, null codec",
+    );
+}
+
+fn bench_big_schema_read_10000_record_deflate(c: &mut Criterion) {
+    bench_read_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Deflate,
+        "big schema, read 10k records, deflate codec",
+    );
+}
+
+fn bench_big_schema_read_10000_record_snappy(c: &mut Criterion) {
+    bench_read_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Snappy,
+        "big schema, read 10k records, snappy codec",
+    );
+}
+
+fn bench_big_schema_read_10000_record_zstd(c: &mut Criterion) {
+    bench_read_codec(
+        c,
+        make_big_record,
+        10000,
+        Codec::Zstd,
+        "big schema, read 10k records, zstd codec",
+    );
+}
+
 criterion_group!(
     benches,
     bench_small_schema_write_1_record,
@@ -301,10 +460,20 @@ criterion_group!(
     config = Criterion::default().sample_size(20).measurement_time(Duration::from_secs(10));
     targets =
         bench_file_quickstop_null,
+        bench_file_quickstop_null_streaming,
         bench_small_schema_write_10000_record,
         bench_small_schema_read_10000_record,
         bench_big_schema_read_10000_record,
-        bench_big_schema_write_10000_record
+        bench_big_schema_write_10000_record,
+        bench_big_schema_write_10000_record_streaming,
+        bench_big_schema_write_10000_record_null,
+        bench_big_schema_write_10000_record_deflate,
+        bench_big_schema_write_10000_record_snappy,
+        bench_big_schema_write_10000_record_zstd,
+        bench_big_schema_read_10000_record_null,
+        bench_big_schema_read_10000_record_deflate,
+        bench_big_schema_read_10000_record_snappy,
+        bench_big_schema_read_10000_record_zstd
 );
 
 criterion_group!(
@@ -0,0 +1,134 @@
+//! Block compression codecs for the Avro container format, negotiated
+//! through the file header's `avro.codec` metadata key.
+
+use std::io::{Read, Write};
+
+use crate::error::{Error, Result};
+
+/// A block compression codec understood by [`crate::writer::Writer`] and
+/// [`crate::reader::Reader`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    /// No compression; each block's bytes are written as-is.
+    Null,
+    /// Raw DEFLATE (RFC 1951), with no zlib or gzip framing.
+    Deflate,
+    /// Google Snappy. Per the Avro spec, each compressed block is followed
+    /// by a 4-byte big-endian CRC32 of the *uncompressed* block data.
+    Snappy,
+    /// Zstandard, at the default compression level.
+    Zstd,
+}
+
+impl Codec {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Codec::Null => "null",
+            Codec::Deflate => "deflate",
+            Codec::Snappy => "snappy",
+            Codec::Zstd => "zstd",
+        }
+    }
+
+    pub(crate) fn parse(name: &str) -> Result<Codec> {
+        match name {
+            "null" => Ok(Codec::Null),
+            "deflate" => Ok(Codec::Deflate),
+            "snappy" => Ok(Codec::Snappy),
+            "zstd" => Ok(Codec::Zstd),
+            other => Err(Error::NotAvro(format!("unsupported codec: {}", other))),
+        }
+    }
+
+    pub(crate) fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Codec::Null => Ok(data.to_vec()),
+            Codec::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).map_err(Error::Io)?;
+                encoder.finish().map_err(Error::Io)
+            }
+            Codec::Snappy => {
+                let mut compressed = snap::raw::Encoder::new()
+                    .compress_vec(data)
+                    .map_err(|e| Error::Encode(e.to_string()))?;
+                compressed.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+                Ok(compressed)
+            }
+            Codec::Zstd => zstd::stream::encode_all(data, 0).map_err(Error::Io),
+        }
+    }
+
+    pub(crate) fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Codec::Null => Ok(data.to_vec()),
+            Codec::Deflate => {
+                let mut decoder = flate2::read::DeflateDecoder::new(data);
+                let mut out = Vec::new();
+                decoder.read_to_end(&mut out).map_err(Error::Io)?;
+                Ok(out)
+            }
+            Codec::Snappy => {
+                if data.len() < 4 {
+                    return Err(Error::Decode("snappy block is shorter than its CRC32 trailer".to_string()));
+                }
+                let (payload, crc_bytes) = data.split_at(data.len() - 4);
+                let out = snap::raw::Decoder::new()
+                    .decompress_vec(payload)
+                    .map_err(|e| Error::Decode(e.to_string()))?;
+
+                let expected_crc = u32::from_be_bytes(crc_bytes.try_into().expect("split at len - 4"));
+                if crc32fast::hash(&out) != expected_crc {
+                    return Err(Error::Decode("snappy block failed its CRC32 check".to_string()));
+                }
+                Ok(out)
+            }
+            Codec::Zstd => zstd::stream::decode_all(data).map_err(Error::Io),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODECS: [Codec; 4] = [Codec::Null, Codec::Deflate, Codec::Snappy, Codec::Zstd];
+
+    #[test]
+    fn round_trips_through_compress_and_decompress() {
+        let data = b"the quick brown fox jumps over the lazy dog".repeat(64);
+        for codec in CODECS {
+            let compressed = codec.compress(&data).unwrap();
+            let decompressed = codec.decompress(&compressed).unwrap();
+            assert_eq!(decompressed, data, "codec {:?} failed to round trip", codec);
+        }
+    }
+
+    #[test]
+    fn round_trips_empty_input() {
+        for codec in CODECS {
+            let compressed = codec.compress(&[]).unwrap();
+            assert_eq!(codec.decompress(&compressed).unwrap(), Vec::<u8>::new());
+        }
+    }
+
+    #[test]
+    fn as_str_and_parse_round_trip() {
+        for codec in CODECS {
+            assert_eq!(Codec::parse(codec.as_str()).unwrap(), codec);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_unknown_codec() {
+        assert!(Codec::parse("bzip2").is_err());
+    }
+
+    #[test]
+    fn snappy_decompress_rejects_bad_crc() {
+        let mut compressed = Codec::Snappy.compress(b"hello").unwrap();
+        let last = compressed.len() - 1;
+        compressed[last] ^= 0xff;
+        assert!(Codec::Snappy.decompress(&compressed).is_err());
+    }
+}
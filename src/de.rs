@@ -0,0 +1,228 @@
+//! A [`serde::Deserializer`] over [`Value`], the mirror image of [`crate::ser`],
+//! so `#[derive(Deserialize)]` types can be read back out without matching on
+//! `Value` by hand.
+
+use serde::de::{
+    self, value::StrDeserializer, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
+};
+
+use crate::error::{Error, Result};
+use crate::types::Value;
+
+/// Deserializes a `T` out of an Avro [`Value`].
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T> {
+    T::deserialize(Deserializer { value })
+}
+
+pub struct Deserializer<'de> {
+    value: &'de Value,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(value: &'de Value) -> Self {
+        Deserializer { value }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Boolean(b) => visitor.visit_bool(*b),
+            Value::Int(i) => visitor.visit_i32(*i),
+            Value::Long(i) => visitor.visit_i64(*i),
+            Value::Float(f) => visitor.visit_f32(*f),
+            Value::Double(f) => visitor.visit_f64(*f),
+            Value::Bytes(b) => visitor.visit_bytes(b),
+            Value::Fixed(_, b) => visitor.visit_bytes(b),
+            Value::String(s) => visitor.visit_str(s),
+            Value::Enum(_, s) => visitor.visit_str(s),
+            Value::Union(inner) => Deserializer::new(inner).deserialize_any(visitor),
+            Value::Array(items) => visitor.visit_seq(SeqDeserializer { iter: items.iter() }),
+            Value::Map(map) => visitor.visit_map(MapDeserializer {
+                iter: map.iter(),
+                value: None,
+            }),
+            Value::Record(fields) => visitor.visit_map(RecordDeserializer {
+                iter: fields.iter(),
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Union(inner) => match inner.as_ref() {
+                Value::Null => visitor.visit_none(),
+                other => visitor.visit_some(Deserializer::new(other)),
+            },
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(Deserializer::new(other)),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.value {
+            Value::Enum(_, symbol) => visitor.visit_enum(EnumDeserializer { variant: symbol }),
+            Value::String(s) => visitor.visit_enum(EnumDeserializer { variant: s }),
+            other => Err(Error::Decode(format!("cannot deserialize enum from {:?}", other))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqDeserializer<'de> {
+    iter: std::slice::Iter<'de, Value>,
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(Deserializer::new(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct MapDeserializer<'de> {
+    iter: std::collections::hash_map::Iter<'de, String, Value>,
+    value: Option<&'de Value>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self.value.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::new(value))
+    }
+}
+
+struct RecordDeserializer<'de> {
+    iter: std::slice::Iter<'de, (String, Value)>,
+    value: Option<&'de Value>,
+}
+
+impl<'de> MapAccess<'de> for RecordDeserializer<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self.value.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::new(value))
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: &'de str,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = UnitOnlyVariantAccess;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let value = seed.deserialize(StrDeserializer::<Error>::new(self.variant))?;
+        Ok((value, UnitOnlyVariantAccess))
+    }
+}
+
+struct UnitOnlyVariantAccess;
+
+impl<'de> VariantAccess<'de> for UnitOnlyVariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, _seed: T) -> Result<T::Value> {
+        Err(Error::Decode("data-carrying enum variants are not supported".to_string()))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
+        Err(Error::Decode("data-carrying enum variants are not supported".to_string()))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value> {
+        Err(Error::Decode("data-carrying enum variants are not supported".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::from_value;
+    use crate::ser::to_value;
+    use crate::types::Value;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+        label: Option<String>,
+    }
+
+    #[test]
+    fn struct_round_trips_through_value() {
+        let point = Point {
+            x: 1,
+            y: -2,
+            label: Some("origin".to_string()),
+        };
+
+        let value = to_value(&point).unwrap();
+        assert_eq!(from_value::<Point>(&value).unwrap(), point);
+    }
+
+    #[test]
+    fn none_round_trips_as_null_union() {
+        let point = Point { x: 0, y: 0, label: None };
+
+        let value = to_value(&point).unwrap();
+        assert_eq!(from_value::<Point>(&value).unwrap(), point);
+    }
+
+    #[test]
+    fn vec_round_trips_through_value() {
+        let numbers = vec![1i64, 2, 3];
+        let value = to_value(&numbers).unwrap();
+        assert_eq!(value, Value::Array(vec![Value::Long(1), Value::Long(2), Value::Long(3)]));
+        assert_eq!(from_value::<Vec<i64>>(&value).unwrap(), numbers);
+    }
+}
@@ -0,0 +1,146 @@
+//! Logic for decoding Avro values from their binary representation, given a [`Schema`].
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::error::{Error, Result};
+use crate::schema::Schema;
+use crate::types::Value;
+use crate::util::{checked_len, decode_variable, zag_i64};
+
+/// Decodes a single value of type `schema` from `reader`.
+pub fn decode<R: Read>(schema: &Schema, reader: &mut R) -> Result<Value> {
+    match schema {
+        Schema::Null => Ok(Value::Null),
+        Schema::Boolean => {
+            let mut byte = [0u8; 1];
+            reader.read_exact(&mut byte).map_err(Error::Io)?;
+            Ok(Value::Boolean(byte[0] != 0))
+        }
+        Schema::Int => Ok(Value::Int(zag_i64(reader)? as i32)),
+        Schema::Long => Ok(Value::Long(zag_i64(reader)?)),
+        Schema::Float => {
+            let mut buf = [0u8; 4];
+            reader.read_exact(&mut buf).map_err(Error::Io)?;
+            Ok(Value::Float(f32::from_le_bytes(buf)))
+        }
+        Schema::Double => {
+            let mut buf = [0u8; 8];
+            reader.read_exact(&mut buf).map_err(Error::Io)?;
+            Ok(Value::Double(f64::from_le_bytes(buf)))
+        }
+        Schema::Bytes => Ok(Value::Bytes(decode_bytes(reader)?)),
+        Schema::String => {
+            let bytes = decode_bytes(reader)?;
+            String::from_utf8(bytes)
+                .map(Value::String)
+                .map_err(|e| Error::Decode(e.to_string()))
+        }
+        Schema::Fixed { size, .. } => {
+            let mut buf = vec![0u8; *size];
+            reader.read_exact(&mut buf).map_err(Error::Io)?;
+            Ok(Value::Fixed(*size, buf))
+        }
+        Schema::Enum { symbols, .. } => {
+            let index = zag_i64(reader)?;
+            let symbol = symbols
+                .get(index as usize)
+                .ok_or_else(|| Error::Decode(format!("enum index {} out of bounds", index)))?;
+            Ok(Value::Enum(index as i32, symbol.clone()))
+        }
+        Schema::Union(union) => {
+            let index = zag_i64(reader)?;
+            let variant_schema = union
+                .variants()
+                .get(index as usize)
+                .ok_or_else(|| Error::Decode(format!("union index {} out of bounds", index)))?;
+            Ok(Value::Union(Box::new(decode(variant_schema, reader)?)))
+        }
+        Schema::Array(inner) => {
+            let mut items = Vec::new();
+            loop {
+                let count = decode_block_count(reader)?;
+                if count == 0 {
+                    break;
+                }
+                for _ in 0..count {
+                    items.push(decode(inner, reader)?);
+                }
+            }
+            Ok(Value::Array(items))
+        }
+        Schema::Map(inner) => {
+            let mut items = HashMap::new();
+            loop {
+                let count = decode_block_count(reader)?;
+                if count == 0 {
+                    break;
+                }
+                for _ in 0..count {
+                    let key_bytes = decode_bytes(reader)?;
+                    let key = String::from_utf8(key_bytes).map_err(|e| Error::Decode(e.to_string()))?;
+                    items.insert(key, decode(inner, reader)?);
+                }
+            }
+            Ok(Value::Map(items))
+        }
+        Schema::Record { fields, .. } => {
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                values.push((field.name.clone(), decode(&field.schema, reader)?));
+            }
+            Ok(Value::Record(values))
+        }
+        Schema::Ref(name) => Err(Error::Decode(format!(
+            "cannot decode against unresolved schema reference `{}`; recursive schemas are not yet supported for decoding",
+            name
+        ))),
+    }
+}
+
+/// Decodes a block count, as used by `array` and `map` encodings: a negative
+/// count is followed by its absolute byte-size, which this function reads
+/// and discards.
+pub(crate) fn decode_block_count<R: Read>(reader: &mut R) -> Result<i64> {
+    let count = zag_i64(reader)?;
+    if count < 0 {
+        let _size = decode_variable(reader)?;
+        Ok(-count)
+    } else {
+        Ok(count)
+    }
+}
+
+pub(crate) fn decode_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = checked_len(zag_i64(reader)?, "bytes")?;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).map_err(Error::Io)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::to_bytes;
+
+    #[test]
+    fn round_trips_bytes_and_string() {
+        let value = Value::Bytes(vec![1, 2, 3]);
+        let bytes = to_bytes(&value, &Schema::Bytes);
+        assert_eq!(decode(&Schema::Bytes, &mut bytes.as_slice()).unwrap(), value);
+    }
+
+    #[test]
+    fn rejects_a_crafted_huge_length_prefix_instead_of_aborting() {
+        // A zig-zag-encoded varint for `i64::MAX`, as the length prefix of a
+        // `bytes` value: ten bytes, no further data. Previously this
+        // allocated a `Vec` sized directly off the attacker-controlled
+        // length and aborted the process with an allocation failure instead
+        // of returning an `Err`.
+        let mut buffer = Vec::new();
+        crate::util::encode_variable(crate::util::zig_zag_encode(i64::MAX), &mut buffer);
+
+        let err = decode(&Schema::Bytes, &mut buffer.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::Decode(_)), "expected a catchable Decode error, got {:?}", err);
+    }
+}
@@ -0,0 +1,118 @@
+//! Logic for encoding Avro values into their binary representation, given a [`Schema`].
+
+use crate::schema::Schema;
+use crate::types::Value;
+use crate::util::{encode_variable, zig_zag_encode};
+
+/// Encodes `value` into `buffer` according to `schema`.
+///
+/// The caller is responsible for ensuring `value` was validated against
+/// `schema` beforehand; this function does not re-validate.
+pub fn encode(value: &Value, schema: &Schema, buffer: &mut Vec<u8>) {
+    match (value, schema) {
+        (Value::Null, Schema::Null) => {}
+        (Value::Boolean(b), Schema::Boolean) => buffer.push(if *b { 1 } else { 0 }),
+        (Value::Int(i), Schema::Int) => encode_variable(zig_zag_encode(i64::from(*i)), buffer),
+        (Value::Int(i), Schema::Long) => encode_variable(zig_zag_encode(i64::from(*i)), buffer),
+        (Value::Long(i), Schema::Long) => encode_variable(zig_zag_encode(*i), buffer),
+        (Value::Float(x), Schema::Float) => buffer.extend_from_slice(&x.to_le_bytes()),
+        (Value::Float(x), Schema::Double) => buffer.extend_from_slice(&(f64::from(*x)).to_le_bytes()),
+        (Value::Double(x), Schema::Double) => buffer.extend_from_slice(&x.to_le_bytes()),
+        (Value::Bytes(bytes), Schema::Bytes) => encode_bytes(bytes, buffer),
+        (Value::String(s), Schema::String) => encode_bytes(s.as_bytes(), buffer),
+        (Value::String(s), Schema::Bytes) => encode_bytes(s.as_bytes(), buffer),
+        (Value::Bytes(bytes), Schema::String) => encode_bytes(bytes, buffer),
+        (Value::Fixed(_, bytes), Schema::Fixed { .. }) => buffer.extend_from_slice(bytes),
+        (Value::Enum(i, _), Schema::Enum { .. }) => encode_variable(zig_zag_encode(i64::from(*i)), buffer),
+        (Value::String(s), Schema::Enum { symbols, .. }) => {
+            if let Some(index) = symbols.iter().position(|symbol| symbol == s) {
+                encode_variable(zig_zag_encode(index as i64), buffer);
+            }
+        }
+        (Value::Union(inner), Schema::Union(union)) => {
+            if let Some((index, variant_schema)) = union.find_schema(inner) {
+                encode_variable(zig_zag_encode(index as i64), buffer);
+                encode(inner, variant_schema, buffer);
+            }
+        }
+        (v, Schema::Union(union)) => {
+            if let Some((index, variant_schema)) = union.find_schema(v) {
+                encode_variable(zig_zag_encode(index as i64), buffer);
+                encode(v, variant_schema, buffer);
+            }
+        }
+        (Value::Array(items), Schema::Array(inner)) => {
+            if !items.is_empty() {
+                encode_variable(zig_zag_encode(items.len() as i64), buffer);
+                for item in items {
+                    encode(item, inner, buffer);
+                }
+            }
+            buffer.push(0);
+        }
+        (Value::Map(items), Schema::Map(inner)) => {
+            if !items.is_empty() {
+                encode_variable(zig_zag_encode(items.len() as i64), buffer);
+                for (key, value) in items {
+                    encode_bytes(key.as_bytes(), buffer);
+                    encode(value, inner, buffer);
+                }
+            }
+            buffer.push(0);
+        }
+        (Value::Record(fields), Schema::Record { fields: schema_fields, .. }) => {
+            for ((_, value), field) in fields.iter().zip(schema_fields.iter()) {
+                encode(value, &field.schema, buffer);
+            }
+        }
+        (_, _) => {}
+    }
+}
+
+/// Encodes `value` against `schema`, returning the resulting bytes.
+pub fn to_bytes(value: &Value, schema: &Schema) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    encode(value, schema, &mut buffer);
+    buffer
+}
+
+fn encode_bytes(bytes: &[u8], buffer: &mut Vec<u8>) {
+    encode_variable(zig_zag_encode(bytes.len() as i64), buffer);
+    buffer.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::decode;
+    use crate::schema::Schema;
+
+    #[test]
+    fn union_of_two_named_records_round_trips_through_binary() {
+        let schema = Schema::parse_str(
+            r#"[
+                {"type": "record", "name": "Dog", "fields": [{"name": "bark", "type": "string"}]},
+                {"type": "record", "name": "Cat", "fields": [{"name": "meow", "type": "string"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let cat = Value::Union(Box::new(Value::Record(vec![("meow".to_string(), Value::String("mrow".to_string()))])));
+        let bytes = to_bytes(&cat, &schema);
+        // Previously `find_schema` looked up by `canonical_kind()` (always
+        // "record" for both branches), so this silently encoded nothing.
+        assert!(!bytes.is_empty(), "encoding a union-of-records branch must not be a no-op");
+        assert_eq!(decode(&schema, &mut bytes.as_slice()).unwrap(), cat);
+    }
+
+    #[test]
+    fn union_prefers_the_exact_branch_over_a_wider_promotion() {
+        let schema = Schema::parse_str(r#"["long", "int"]"#).unwrap();
+
+        let value = Value::Union(Box::new(Value::Int(5)));
+        let bytes = to_bytes(&value, &schema);
+        // Previously the first promotion-compatible branch ("long") won
+        // regardless of order, silently widening the value.
+        assert_eq!(decode(&schema, &mut bytes.as_slice()).unwrap(), value);
+    }
+}
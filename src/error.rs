@@ -0,0 +1,46 @@
+//! Error types returned by this crate.
+
+use thiserror::Error;
+
+/// A specialized `Result` type for Avro operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The error type for all fallible operations in this crate.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("failed to parse schema: {0}")]
+    ParseSchema(String),
+
+    #[error("failed to validate value {0:?} against schema {1:?}")]
+    Validation(Box<crate::types::Value>, Box<crate::schema::Schema>),
+
+    #[error("failed to encode value: {0}")]
+    Encode(String),
+
+    #[error("failed to decode value: {0}")]
+    Decode(String),
+
+    #[error("failed to resolve schema: {0}")]
+    SchemaResolution(String),
+
+    #[error("not a valid Avro data file: {0}")]
+    NotAvro(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Encode(msg.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Decode(msg.to_string())
+    }
+}
@@ -0,0 +1,31 @@
+//! # avro-rs
+//!
+//! A library for working with [Apache Avro](https://avro.apache.org/) in Rust.
+//!
+//! Apache Avro is a data serialization system that relies on schemas to
+//! define the semantics and structure of the encoded data. This crate
+//! provides pure Rust support for reading and writing Avro data, both in its
+//! binary and container file representations.
+
+pub mod codec;
+pub mod de;
+pub mod decode;
+pub mod encode;
+pub mod error;
+pub mod rabin;
+pub mod reader;
+pub mod resolve;
+pub mod schema;
+pub mod ser;
+pub mod single_object;
+pub mod types;
+pub(crate) mod util;
+pub mod writer;
+
+pub use crate::codec::Codec;
+pub use crate::de::from_value;
+pub use crate::error::{Error, Result};
+pub use crate::reader::Reader;
+pub use crate::ser::to_value;
+pub use crate::single_object::{SingleObjectReader, SingleObjectWriter};
+pub use crate::writer::Writer;
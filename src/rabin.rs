@@ -0,0 +1,72 @@
+//! CRC-64-AVRO Rabin fingerprinting of schemas, as defined by the
+//! [Avro spec](https://avro.apache.org/docs/current/spec.html#schema_fingerprints).
+//!
+//! The fingerprint is computed over a schema's
+//! [`Schema::canonical_form`](crate::schema::Schema::canonical_form), not its
+//! original source text, so that two schemas that differ only in
+//! inconsequential ways (the order of attributes within a JSON object, extra
+//! whitespace, a `doc` attribute) still fingerprint identically. Record
+//! fields keep their declared order, so reordering them changes the
+//! fingerprint.
+
+use crate::schema::Schema;
+
+const EMPTY: u64 = 0xc15d_213a_a4d7_a795;
+
+const fn build_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut fp = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            fp = (fp >> 1) ^ (EMPTY & (0u64.wrapping_sub(fp & 1)));
+            j += 1;
+        }
+        table[i] = fp;
+        i += 1;
+    }
+    table
+}
+
+const FINGERPRINT_TABLE: [u64; 256] = build_table();
+
+/// Computes the 64-bit CRC-64-AVRO Rabin fingerprint of `schema`.
+pub fn fingerprint(schema: &Schema) -> u64 {
+    fingerprint_of_canonical_form(schema.canonical_form().as_bytes())
+}
+
+fn fingerprint_of_canonical_form(bytes: &[u8]) -> u64 {
+    let mut fp = EMPTY;
+    for &b in bytes {
+        fp = (fp >> 8) ^ FINGERPRINT_TABLE[((fp ^ u64::from(b)) & 0xff) as usize];
+    }
+    fp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_deterministic() {
+        assert_eq!(fingerprint(&Schema::String), fingerprint(&Schema::String));
+    }
+
+    #[test]
+    fn differs_for_structurally_different_schemas() {
+        assert_ne!(fingerprint(&Schema::Long), fingerprint(&Schema::Int));
+    }
+
+    #[test]
+    fn ignores_schema_doc_via_canonical_form() {
+        let with_doc = Schema::parse_str(
+            r#"{"type": "record", "name": "R", "doc": "irrelevant", "fields": [{"name": "f", "type": "string"}]}"#,
+        )
+        .unwrap();
+        let without_doc =
+            Schema::parse_str(r#"{"type": "record", "name": "R", "fields": [{"name": "f", "type": "string"}]}"#)
+                .unwrap();
+        assert_eq!(fingerprint(&with_doc), fingerprint(&without_doc));
+    }
+}
@@ -0,0 +1,175 @@
+//! Logic handling reading in Avro format at user level.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::codec::Codec;
+use crate::decode::decode;
+use crate::error::{Error, Result};
+use crate::resolve::resolve;
+use crate::schema::Schema;
+use crate::types::Value;
+use crate::util::{checked_len, zag_i64};
+use crate::writer::MAGIC_BYTES;
+
+const SYNC_SIZE: usize = 16;
+
+/// The container-format header of a `.avro` byte stream, as returned by
+/// [`read_header`]: its metadata map and sync marker.
+pub(crate) struct Header {
+    pub metadata: HashMap<String, Vec<u8>>,
+    pub marker: [u8; SYNC_SIZE],
+}
+
+pub(crate) fn read_header<R: Read>(reader: &mut R) -> Result<Header> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(Error::Io)?;
+    if &magic != MAGIC_BYTES {
+        return Err(Error::NotAvro("missing Avro magic bytes".to_string()));
+    }
+
+    let metadata_schema = Schema::Map(Box::new(Schema::Bytes));
+    let metadata = match decode(&metadata_schema, reader)? {
+        Value::Map(map) => map
+            .into_iter()
+            .map(|(k, v)| match v {
+                Value::Bytes(bytes) => Ok((k, bytes)),
+                _ => Err(Error::NotAvro("invalid header metadata".to_string())),
+            })
+            .collect::<Result<HashMap<_, _>>>()?,
+        _ => return Err(Error::NotAvro("invalid header metadata".to_string())),
+    };
+
+    let mut marker = [0u8; SYNC_SIZE];
+    reader.read_exact(&mut marker).map_err(Error::Io)?;
+
+    Ok(Header { metadata, marker })
+}
+
+/// Iterates over the values of a `.avro` container file, decoding them one
+/// block at a time.
+pub struct Reader<'a, R> {
+    source: R,
+    schema_override: Option<&'a Schema>,
+    resolve_into: Option<&'a Schema>,
+    writer_schema: Schema,
+    codec: Codec,
+    marker: [u8; SYNC_SIZE],
+    block_values: std::vec::IntoIter<Value>,
+}
+
+impl<'a, R: Read> Reader<'a, R> {
+    /// Creates a `Reader`, trusting the schema embedded in the container
+    /// file's header.
+    pub fn new(source: R) -> Result<Reader<'a, R>> {
+        Self::with_schema_opt(source, None, None)
+    }
+
+    /// Creates a `Reader` that decodes every value with `schema`, which must
+    /// match the schema the data was written with.
+    pub fn with_schema(schema: &'a Schema, source: R) -> Result<Reader<'a, R>> {
+        Self::with_schema_opt(source, Some(schema), None)
+    }
+
+    /// Creates a `Reader` that decodes data written under `writer_schema`
+    /// directly into the shape of `reader_schema`, performing
+    /// [Avro schema resolution](crate::resolve) as it goes: numeric
+    /// promotions, enum symbol matching (falling back to the reader's
+    /// declared default), record fields matched by name (reader-only fields
+    /// filled from their default, writer-only fields skipped), and
+    /// union-aware branch matching.
+    pub fn with_resolved_schema(
+        writer_schema: &'a Schema,
+        reader_schema: &'a Schema,
+        source: R,
+    ) -> Result<Reader<'a, R>> {
+        Self::with_schema_opt(source, Some(writer_schema), Some(reader_schema))
+    }
+
+    fn with_schema_opt(
+        mut source: R,
+        schema_override: Option<&'a Schema>,
+        resolve_into: Option<&'a Schema>,
+    ) -> Result<Reader<'a, R>> {
+        let header = read_header(&mut source)?;
+
+        let writer_schema = match header.metadata.get("avro.schema") {
+            Some(raw) => {
+                let json = serde_json::from_slice(raw)?;
+                Schema::parse(&json)?
+            }
+            None => return Err(Error::NotAvro("no `avro.schema` in header".to_string())),
+        };
+
+        let codec = match header.metadata.get("avro.codec") {
+            Some(name) => Codec::parse(&String::from_utf8_lossy(name))?,
+            None => Codec::Null,
+        };
+
+        Ok(Reader {
+            source,
+            schema_override,
+            resolve_into,
+            writer_schema,
+            codec,
+            marker: header.marker,
+            block_values: Vec::new().into_iter(),
+        })
+    }
+
+    /// The schema the data was written with.
+    pub fn writer_schema(&self) -> &Schema {
+        &self.writer_schema
+    }
+
+    fn read_block(&mut self) -> Result<bool> {
+        let count = match zag_i64(&mut self.source) {
+            Ok(count) => count,
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(e) => return Err(e),
+        };
+
+        let byte_size = checked_len(zag_i64(&mut self.source)?, "compressed block")?;
+        let mut compressed = vec![0u8; byte_size];
+        self.source.read_exact(&mut compressed).map_err(Error::Io)?;
+        let block_bytes = self.codec.decompress(&compressed)?;
+        let mut block_reader = block_bytes.as_slice();
+
+        let writer_schema = self.schema_override.unwrap_or(&self.writer_schema);
+        let mut values = Vec::with_capacity(checked_len(count, "block value count")?);
+        for _ in 0..count {
+            let value = match self.resolve_into {
+                Some(reader_schema) => resolve(writer_schema, reader_schema, &mut block_reader)?,
+                None => decode(writer_schema, &mut block_reader)?,
+            };
+            values.push(value);
+        }
+
+        let mut marker = [0u8; SYNC_SIZE];
+        self.source.read_exact(&mut marker).map_err(Error::Io)?;
+        if marker != self.marker {
+            return Err(Error::NotAvro("block sync marker mismatch".to_string()));
+        }
+
+        self.block_values = values.into_iter();
+        Ok(true)
+    }
+}
+
+impl<'a, R: Read> Iterator for Reader<'a, R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.block_values.next() {
+                return Some(Ok(value));
+            }
+
+            match self.read_block() {
+                Ok(true) => continue,
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
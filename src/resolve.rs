@@ -0,0 +1,433 @@
+//! [Schema resolution](https://avro.apache.org/docs/current/spec.html#Schema+Resolution):
+//! decoding data written under a *writer* schema directly into the shape of
+//! a different, compatible *reader* schema, as happens whenever a consumer's
+//! schema has evolved since the data was produced.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::decode::{decode, decode_block_count, decode_bytes};
+use crate::error::{Error, Result};
+use crate::schema::{RecordField, Schema};
+use crate::types::{code_points_to_bytes, Value};
+use crate::util::zag_i64;
+
+/// Decodes a value from `reader`, which was written against `writer_schema`,
+/// resolving it into the shape described by `reader_schema`.
+pub fn resolve<R: Read>(writer_schema: &Schema, reader_schema: &Schema, reader: &mut R) -> Result<Value> {
+    match (writer_schema, reader_schema) {
+        (Schema::Union(writer_union), _) => {
+            let index = zag_i64(reader)?;
+            let writer_variant = writer_union
+                .variants()
+                .get(index as usize)
+                .ok_or_else(|| Error::Decode(format!("union index {} out of bounds", index)))?;
+            match reader_schema {
+                Schema::Union(_) => resolve_into_union_branch(writer_variant, reader_schema, reader),
+                other => resolve(writer_variant, other, reader),
+            }
+        }
+        (w, Schema::Union(reader_union)) => {
+            let branch = reader_union
+                .variants()
+                .iter()
+                .find(|r| is_compatible(w, r))
+                .ok_or_else(|| {
+                    Error::SchemaResolution(format!(
+                        "no branch of reader union is compatible with writer schema {:?}",
+                        w
+                    ))
+                })?;
+            Ok(Value::Union(Box::new(resolve(w, branch, reader)?)))
+        }
+
+        (Schema::Null, Schema::Null) => Ok(Value::Null),
+        (Schema::Boolean, Schema::Boolean) => decode(writer_schema, reader),
+
+        (Schema::Int, Schema::Int) => decode(writer_schema, reader),
+        (Schema::Int, Schema::Long) => Ok(Value::Long(i64::from(read_int(reader)?))),
+        (Schema::Int, Schema::Float) => Ok(Value::Float(read_int(reader)? as f32)),
+        (Schema::Int, Schema::Double) => Ok(Value::Double(f64::from(read_int(reader)?))),
+
+        (Schema::Long, Schema::Long) => decode(writer_schema, reader),
+        (Schema::Long, Schema::Float) => Ok(Value::Float(zag_i64(reader)? as f32)),
+        (Schema::Long, Schema::Double) => Ok(Value::Double(zag_i64(reader)? as f64)),
+
+        (Schema::Float, Schema::Float) => decode(writer_schema, reader),
+        (Schema::Float, Schema::Double) => {
+            let mut buf = [0u8; 4];
+            reader.read_exact(&mut buf).map_err(Error::Io)?;
+            Ok(Value::Double(f64::from(f32::from_le_bytes(buf))))
+        }
+        (Schema::Double, Schema::Double) => decode(writer_schema, reader),
+
+        (Schema::String, Schema::String) => decode(writer_schema, reader),
+        (Schema::Bytes, Schema::Bytes) => decode(writer_schema, reader),
+        (Schema::String, Schema::Bytes) => Ok(Value::Bytes(decode_bytes(reader)?)),
+        (Schema::Bytes, Schema::String) => {
+            let bytes = decode_bytes(reader)?;
+            String::from_utf8(bytes)
+                .map(Value::String)
+                .map_err(|e| Error::Decode(e.to_string()))
+        }
+
+        (Schema::Fixed { size: w_size, .. }, Schema::Fixed { size: r_size, .. }) if w_size == r_size => {
+            decode(writer_schema, reader)
+        }
+
+        (
+            Schema::Enum {
+                symbols: writer_symbols,
+                ..
+            },
+            Schema::Enum {
+                symbols: reader_symbols,
+                default,
+                ..
+            },
+        ) => {
+            let index = zag_i64(reader)?;
+            let symbol = writer_symbols
+                .get(index as usize)
+                .ok_or_else(|| Error::Decode(format!("enum index {} out of bounds", index)))?;
+            if let Some(reader_index) = reader_symbols.iter().position(|s| s == symbol) {
+                Ok(Value::Enum(reader_index as i32, symbol.clone()))
+            } else if let Some(default) = default {
+                let reader_index = reader_symbols.iter().position(|s| s == default).unwrap_or(0);
+                Ok(Value::Enum(reader_index as i32, default.clone()))
+            } else {
+                Err(Error::SchemaResolution(format!(
+                    "writer symbol `{}` is not in the reader's enum and it declares no default",
+                    symbol
+                )))
+            }
+        }
+
+        (Schema::Array(writer_items), Schema::Array(reader_items)) => {
+            let mut items = Vec::new();
+            loop {
+                let count = decode_block_count(reader)?;
+                if count == 0 {
+                    break;
+                }
+                for _ in 0..count {
+                    items.push(resolve(writer_items, reader_items, reader)?);
+                }
+            }
+            Ok(Value::Array(items))
+        }
+        (Schema::Map(writer_values), Schema::Map(reader_values)) => {
+            let mut items = HashMap::new();
+            loop {
+                let count = decode_block_count(reader)?;
+                if count == 0 {
+                    break;
+                }
+                for _ in 0..count {
+                    let key_bytes = decode_bytes(reader)?;
+                    let key = String::from_utf8(key_bytes).map_err(|e| Error::Decode(e.to_string()))?;
+                    items.insert(key, resolve(writer_values, reader_values, reader)?);
+                }
+            }
+            Ok(Value::Map(items))
+        }
+
+        (
+            Schema::Record {
+                fields: writer_fields,
+                ..
+            },
+            Schema::Record {
+                fields: reader_fields,
+                ..
+            },
+        ) => resolve_record(writer_fields, reader_fields, reader),
+
+        (w, r) => Err(Error::SchemaResolution(format!(
+            "cannot resolve writer schema {:?} into reader schema {:?}",
+            w, r
+        ))),
+    }
+}
+
+fn read_int<R: Read>(reader: &mut R) -> Result<i32> {
+    Ok(zag_i64(reader)? as i32)
+}
+
+fn resolve_into_union_branch<R: Read>(
+    writer_variant: &Schema,
+    reader_schema: &Schema,
+    reader: &mut R,
+) -> Result<Value> {
+    match reader_schema {
+        Schema::Union(reader_union) => {
+            let branch = reader_union
+                .variants()
+                .iter()
+                .find(|r| is_compatible(writer_variant, r))
+                .ok_or_else(|| {
+                    Error::SchemaResolution(format!(
+                        "no branch of reader union is compatible with writer schema {:?}",
+                        writer_variant
+                    ))
+                })?;
+            Ok(Value::Union(Box::new(resolve(writer_variant, branch, reader)?)))
+        }
+        other => resolve(writer_variant, other, reader),
+    }
+}
+
+fn resolve_record<R: Read>(
+    writer_fields: &[RecordField],
+    reader_fields: &[RecordField],
+    reader: &mut R,
+) -> Result<Value> {
+    let mut decoded: HashMap<String, Value> = HashMap::new();
+
+    for writer_field in writer_fields {
+        match reader_fields.iter().find(|f| f.name == writer_field.name) {
+            Some(reader_field) => {
+                let value = resolve(&writer_field.schema, &reader_field.schema, reader)?;
+                decoded.insert(writer_field.name.clone(), value);
+            }
+            // the writer wrote a field the reader no longer cares about: decode it to advance
+            // the cursor, then discard it.
+            None => {
+                decode(&writer_field.schema, reader)?;
+            }
+        }
+    }
+
+    let mut values = Vec::with_capacity(reader_fields.len());
+    for reader_field in reader_fields {
+        let value = match decoded.remove(&reader_field.name) {
+            Some(value) => value,
+            None => match &reader_field.default {
+                Some(default) => default_to_value(default, &reader_field.schema)?,
+                None => {
+                    return Err(Error::SchemaResolution(format!(
+                        "reader field `{}` is missing from the writer schema and has no default",
+                        reader_field.name
+                    )))
+                }
+            },
+        };
+        values.push((reader_field.name.clone(), value));
+    }
+
+    Ok(Value::Record(values))
+}
+
+/// Whether a value written under `writer` can be resolved into `reader`,
+/// without actually decoding anything.
+fn is_compatible(writer: &Schema, reader: &Schema) -> bool {
+    match (writer, reader) {
+        (Schema::Null, Schema::Null) | (Schema::Boolean, Schema::Boolean) => true,
+        (Schema::Int, Schema::Int | Schema::Long | Schema::Float | Schema::Double) => true,
+        (Schema::Long, Schema::Long | Schema::Float | Schema::Double) => true,
+        (Schema::Float, Schema::Float | Schema::Double) => true,
+        (Schema::Double, Schema::Double) => true,
+        (Schema::Bytes, Schema::Bytes | Schema::String) => true,
+        (Schema::String, Schema::String | Schema::Bytes) => true,
+        (Schema::Array(w), Schema::Array(r)) => is_compatible(w, r),
+        (Schema::Map(w), Schema::Map(r)) => is_compatible(w, r),
+        (Schema::Fixed { size: w, .. }, Schema::Fixed { size: r, .. }) => w == r,
+        (Schema::Enum { .. }, Schema::Enum { .. }) => true,
+        (Schema::Record { name: w, .. }, Schema::Record { name: r, .. }) => w.name == r.name,
+        (Schema::Union(w), r) => w.variants().iter().any(|v| is_compatible(v, r)),
+        (w, Schema::Union(r)) => r.variants().iter().any(|v| is_compatible(w, v)),
+        _ => false,
+    }
+}
+
+/// Builds a [`Value`] out of a JSON schema `default`, per the Avro spec's
+/// rules for default values (e.g. `bytes`/`fixed` defaults are JSON strings
+/// of escaped code points).
+fn default_to_value(default: &serde_json::Value, schema: &Schema) -> Result<Value> {
+    use serde_json::Value as Json;
+
+    match (schema, default) {
+        (Schema::Null, Json::Null) => Ok(Value::Null),
+        (Schema::Boolean, Json::Bool(b)) => Ok(Value::Boolean(*b)),
+        (Schema::Int, Json::Number(n)) => n
+            .as_i64()
+            .map(|n| Value::Int(n as i32))
+            .ok_or_else(|| Error::SchemaResolution(format!("invalid int default: {}", n))),
+        (Schema::Long, Json::Number(n)) => n
+            .as_i64()
+            .map(Value::Long)
+            .ok_or_else(|| Error::SchemaResolution(format!("invalid long default: {}", n))),
+        (Schema::Float, Json::Number(n)) => n
+            .as_f64()
+            .map(|n| Value::Float(n as f32))
+            .ok_or_else(|| Error::SchemaResolution(format!("invalid float default: {}", n))),
+        (Schema::Double, Json::Number(n)) => n
+            .as_f64()
+            .map(Value::Double)
+            .ok_or_else(|| Error::SchemaResolution(format!("invalid double default: {}", n))),
+        (Schema::Bytes, Json::String(s)) => Ok(Value::Bytes(code_points_to_bytes(s)?)),
+        (Schema::String, Json::String(s)) => Ok(Value::String(s.clone())),
+        (Schema::Fixed { size, .. }, Json::String(s)) => {
+            let bytes = code_points_to_bytes(s)?;
+            if bytes.len() != *size {
+                return Err(Error::SchemaResolution(format!(
+                    "expected {} bytes for fixed default, got {}",
+                    size,
+                    bytes.len()
+                )));
+            }
+            Ok(Value::Fixed(*size, bytes))
+        }
+        (Schema::Enum { symbols, .. }, Json::String(s)) => {
+            let index = symbols.iter().position(|sym| sym == s).unwrap_or(0);
+            Ok(Value::Enum(index as i32, s.clone()))
+        }
+        (Schema::Array(inner), Json::Array(items)) => Ok(Value::Array(
+            items.iter().map(|item| default_to_value(item, inner)).collect::<Result<_>>()?,
+        )),
+        (Schema::Map(inner), Json::Object(map)) => {
+            let mut out = HashMap::with_capacity(map.len());
+            for (key, value) in map {
+                out.insert(key.clone(), default_to_value(value, inner)?);
+            }
+            Ok(Value::Map(out))
+        }
+        (Schema::Record { fields, .. }, Json::Object(map)) => {
+            let mut out = Vec::with_capacity(fields.len());
+            for field in fields {
+                let value = match map.get(&field.name) {
+                    Some(value) => default_to_value(value, &field.schema)?,
+                    None => match &field.default {
+                        Some(default) => default_to_value(default, &field.schema)?,
+                        None => {
+                            return Err(Error::SchemaResolution(format!(
+                                "record default is missing field `{}`",
+                                field.name
+                            )))
+                        }
+                    },
+                };
+                out.push((field.name.clone(), value));
+            }
+            Ok(Value::Record(out))
+        }
+        // a union's default always describes its first branch
+        (Schema::Union(union), default) => {
+            let first = union
+                .variants()
+                .first()
+                .ok_or_else(|| Error::SchemaResolution("union schema has no variants".to_string()))?;
+            Ok(Value::Union(Box::new(default_to_value(default, first)?)))
+        }
+        (schema, default) => Err(Error::SchemaResolution(format!(
+            "cannot build default {} for schema {:?}",
+            default, schema
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::to_bytes;
+
+    #[test]
+    fn promotes_int_to_long() {
+        let bytes = to_bytes(&Value::Int(7), &Schema::Int);
+        let value = resolve(&Schema::Int, &Schema::Long, &mut bytes.as_slice()).unwrap();
+        assert_eq!(value, Value::Long(7));
+    }
+
+    #[test]
+    fn record_field_added_by_reader_uses_default() {
+        let writer_schema =
+            Schema::parse_str(r#"{"type": "record", "name": "R", "fields": [{"name": "a", "type": "int"}]}"#)
+                .unwrap();
+        let reader_schema = Schema::parse_str(
+            r#"{"type": "record", "name": "R", "fields": [
+                {"name": "a", "type": "int"},
+                {"name": "b", "type": "string", "default": "fallback"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let value = Value::Record(vec![("a".to_string(), Value::Int(1))]);
+        let bytes = to_bytes(&value, &writer_schema);
+
+        let resolved = resolve(&writer_schema, &reader_schema, &mut bytes.as_slice()).unwrap();
+        assert_eq!(
+            resolved,
+            Value::Record(vec![
+                ("a".to_string(), Value::Int(1)),
+                ("b".to_string(), Value::String("fallback".to_string())),
+            ])
+        );
+    }
+
+    #[test]
+    fn record_field_removed_by_reader_is_skipped() {
+        let writer_schema = Schema::parse_str(
+            r#"{"type": "record", "name": "R", "fields": [
+                {"name": "a", "type": "int"},
+                {"name": "b", "type": "string"}
+            ]}"#,
+        )
+        .unwrap();
+        let reader_schema =
+            Schema::parse_str(r#"{"type": "record", "name": "R", "fields": [{"name": "a", "type": "int"}]}"#)
+                .unwrap();
+
+        let value = Value::Record(vec![
+            ("a".to_string(), Value::Int(1)),
+            ("b".to_string(), Value::String("dropped".to_string())),
+        ]);
+        let bytes = to_bytes(&value, &writer_schema);
+
+        let resolved = resolve(&writer_schema, &reader_schema, &mut bytes.as_slice()).unwrap();
+        assert_eq!(resolved, Value::Record(vec![("a".to_string(), Value::Int(1))]));
+    }
+
+    #[test]
+    fn enum_symbol_unknown_to_reader_falls_back_to_default() {
+        let writer_schema =
+            Schema::parse_str(r#"{"type": "enum", "name": "Suit", "symbols": ["SPADES", "HEARTS"]}"#).unwrap();
+        let reader_schema = Schema::parse_str(
+            r#"{"type": "enum", "name": "Suit", "symbols": ["HEARTS"], "default": "HEARTS"}"#,
+        )
+        .unwrap();
+
+        let bytes = to_bytes(&Value::Enum(0, "SPADES".to_string()), &writer_schema);
+        let resolved = resolve(&writer_schema, &reader_schema, &mut bytes.as_slice()).unwrap();
+        assert_eq!(resolved, Value::Enum(0, "HEARTS".to_string()));
+    }
+
+    #[test]
+    fn fixed_default_with_out_of_range_code_point_errors_instead_of_truncating() {
+        let writer_schema =
+            Schema::parse_str(r#"{"type": "record", "name": "R", "fields": [{"name": "a", "type": "int"}]}"#)
+                .unwrap();
+        let reader_schema = Schema::parse_str(
+            r#"{"type": "record", "name": "R", "fields": [
+                {"name": "a", "type": "int"},
+                {"name": "b", "type": {"type": "fixed", "name": "F", "size": 1}, "default": "ł"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let value = Value::Record(vec![("a".to_string(), Value::Int(1))]);
+        let bytes = to_bytes(&value, &writer_schema);
+
+        let err = resolve(&writer_schema, &reader_schema, &mut bytes.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::Decode(_)), "expected the out-of-range code point to error, got {:?}", err);
+    }
+
+    #[test]
+    fn resolves_into_compatible_union_branch() {
+        let writer_schema = Schema::Int;
+        let reader_schema = Schema::parse_str(r#"["null", "long"]"#).unwrap();
+
+        let bytes = to_bytes(&Value::Int(5), &writer_schema);
+        let resolved = resolve(&writer_schema, &reader_schema, &mut bytes.as_slice()).unwrap();
+        assert_eq!(resolved, Value::Union(Box::new(Value::Long(5))));
+    }
+}
@@ -0,0 +1,625 @@
+//! Logic for parsing and interacting with Avro schemas, as per the
+//! [Avro specification](https://avro.apache.org/docs/current/spec.html#schemas).
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::error::{Error, Result};
+
+/// The fully qualified name of a named Avro schema (`record`, `enum`, `fixed`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Name {
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
+impl Name {
+    fn parse(complex: &Map<String, JsonValue>, enclosing_namespace: &Option<String>) -> Result<Self> {
+        let full_name = complex
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::ParseSchema("no `name` field".to_string()))?;
+
+        let namespace = complex
+            .get("namespace")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .or_else(|| enclosing_namespace.clone());
+
+        Self::from_parts(full_name, namespace)
+    }
+
+    fn from_parts(full_name: &str, namespace: Option<String>) -> Result<Self> {
+        if let Some(idx) = full_name.rfind('.') {
+            Ok(Name {
+                name: full_name[idx + 1..].to_string(),
+                namespace: Some(full_name[..idx].to_string()),
+            })
+        } else {
+            Ok(Name {
+                name: full_name.to_string(),
+                namespace,
+            })
+        }
+    }
+
+    /// The fully qualified name, e.g. `com.example.Foo`.
+    pub fn fullname(&self, default_namespace: &Option<String>) -> String {
+        if self.name.contains('.') {
+            self.name.clone()
+        } else {
+            let namespace = self.namespace.as_ref().or(default_namespace.as_ref());
+            match namespace {
+                Some(ns) if !ns.is_empty() => format!("{}.{}", ns, self.name),
+                _ => self.name.clone(),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.fullname(&None))
+    }
+}
+
+/// A field of a `record` schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordField {
+    pub name: String,
+    pub doc: Option<String>,
+    pub default: Option<JsonValue>,
+    pub schema: Schema,
+    pub position: usize,
+}
+
+/// A union of two or more Avro schemas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnionSchema {
+    schemas: Vec<Schema>,
+}
+
+impl UnionSchema {
+    fn new(schemas: Vec<Schema>) -> Result<Self> {
+        let mut seen = std::collections::HashSet::new();
+        for schema in &schemas {
+            let key = match schema.name() {
+                Some(name) => name.fullname(&None),
+                None => schema.canonical_kind(),
+            };
+            if !seen.insert(key) {
+                return Err(Error::ParseSchema(
+                    "unions may not contain more than one schema with the same type, except for named records, fixed and enums".to_string(),
+                ));
+            }
+        }
+        Ok(UnionSchema { schemas })
+    }
+
+    /// Returns the schema variants that make up this union.
+    pub fn variants(&self) -> &[Schema] {
+        &self.schemas
+    }
+
+    /// True if this union has `null` as its first variant, the common
+    /// "optional field" idiom.
+    pub fn is_nullable(&self) -> bool {
+        !self.schemas.is_empty() && self.schemas[0] == Schema::Null
+    }
+
+    /// Finds the branch that matches `value`, returning its index and schema.
+    ///
+    /// Matches structurally via [`Value::validate`](crate::types::Value::validate)
+    /// rather than by `canonical_kind()` alone, since a `record`/`enum`/`fixed`
+    /// value carries no name of its own to look up a branch by (the
+    /// per-name check in [`UnionSchema::new`] only guards against ambiguous
+    /// unions at parse time).
+    ///
+    /// A branch whose kind exactly matches `value`'s is preferred over one
+    /// that merely accepts it through a promotion (`int` -> `long`, `float`
+    /// -> `double`, `string` <-> `bytes`): otherwise a union like
+    /// `["long", "int"]` would resolve every `Value::Int` to its `long`
+    /// branch just because it comes first, silently widening the value.
+    pub fn find_schema(&self, value: &crate::types::Value) -> Option<(usize, &Schema)> {
+        self.schemas
+            .iter()
+            .enumerate()
+            .find(|(_, schema)| schema.canonical_kind() == value.canonical_kind() && value.validate(schema))
+            .or_else(|| self.schemas.iter().enumerate().find(|(_, schema)| value.validate(schema)))
+    }
+}
+
+/// Represents any valid Avro schema.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schema {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Array(Box<Schema>),
+    Map(Box<Schema>),
+    Union(UnionSchema),
+    Record {
+        name: Name,
+        doc: Option<String>,
+        fields: Vec<RecordField>,
+        lookup: HashMap<String, usize>,
+    },
+    Enum {
+        name: Name,
+        doc: Option<String>,
+        symbols: Vec<String>,
+        default: Option<String>,
+    },
+    Fixed {
+        name: Name,
+        size: usize,
+    },
+    /// A reference to a named type (`record`/`enum`/`fixed`) declared
+    /// elsewhere in the same schema document, by its fullname.
+    ///
+    /// Produced when parsing a bare-name reference to a record that is still
+    /// being parsed — the standard way to express recursion (e.g. a
+    /// linked-list `record` whose `next` field refers back to the record
+    /// itself). Inlining the referenced schema isn't possible for a
+    /// self-reference (it would recurse forever), so this variant is kept
+    /// as-is rather than resolved at parse time. A bare-name reference to a
+    /// named type whose parsing has already finished is replaced by a copy
+    /// of that schema instead.
+    Ref(Name),
+}
+
+impl Schema {
+    /// Parses an Avro schema from a JSON-formatted string.
+    pub fn parse_str(input: &str) -> Result<Self> {
+        let value: JsonValue = serde_json::from_str(input)?;
+        Self::parse(&value)
+    }
+
+    /// Parses an Avro schema from a `serde_json::Value`.
+    pub fn parse(value: &JsonValue) -> Result<Self> {
+        let mut names = HashMap::new();
+        Self::parse_with_namespace(value, &None, &mut names)
+    }
+
+    fn parse_with_namespace(
+        value: &JsonValue,
+        enclosing_namespace: &Option<String>,
+        names: &mut HashMap<String, Schema>,
+    ) -> Result<Self> {
+        match value {
+            JsonValue::String(t) => Self::parse_named_or_primitive(t.as_str(), enclosing_namespace, names),
+            JsonValue::Object(complex) => Self::parse_complex(complex, enclosing_namespace, names),
+            JsonValue::Array(variants) => Self::parse_union(variants, enclosing_namespace, names),
+            other => Err(Error::ParseSchema(format!("invalid schema node: {}", other))),
+        }
+    }
+
+    fn parse_primitive(name: &str) -> Result<Self> {
+        match name {
+            "null" => Ok(Schema::Null),
+            "boolean" => Ok(Schema::Boolean),
+            "int" => Ok(Schema::Int),
+            "long" => Ok(Schema::Long),
+            "float" => Ok(Schema::Float),
+            "double" => Ok(Schema::Double),
+            "bytes" => Ok(Schema::Bytes),
+            "string" => Ok(Schema::String),
+            other => Err(Error::ParseSchema(format!("unknown type name: {}", other))),
+        }
+    }
+
+    /// Resolves a bare-name schema node: either a primitive type name, or a
+    /// reference to a named type already registered in `names` (either
+    /// fully parsed, or a [`Schema::Ref`] placeholder for a type that is
+    /// still being parsed further up the call stack, i.e. a recursive
+    /// reference to an enclosing record).
+    fn parse_named_or_primitive(
+        name: &str,
+        enclosing_namespace: &Option<String>,
+        names: &HashMap<String, Schema>,
+    ) -> Result<Self> {
+        if let Ok(schema) = Self::parse_primitive(name) {
+            return Ok(schema);
+        }
+        let fullname = Name::from_parts(name, enclosing_namespace.clone())?.fullname(&None);
+        names
+            .get(&fullname)
+            .or_else(|| names.get(name))
+            .cloned()
+            .ok_or_else(|| Error::ParseSchema(format!("unknown type name: {}", name)))
+    }
+
+    fn parse_union(
+        variants: &[JsonValue],
+        enclosing_namespace: &Option<String>,
+        names: &mut HashMap<String, Schema>,
+    ) -> Result<Self> {
+        let schemas = variants
+            .iter()
+            .map(|v| Self::parse_with_namespace(v, enclosing_namespace, names))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Schema::Union(UnionSchema::new(schemas)?))
+    }
+
+    fn parse_complex(
+        complex: &Map<String, JsonValue>,
+        enclosing_namespace: &Option<String>,
+        names: &mut HashMap<String, Schema>,
+    ) -> Result<Self> {
+        match complex.get("type").and_then(|v| v.as_str()) {
+            Some("record") => Self::parse_record(complex, enclosing_namespace, names),
+            Some("enum") => Self::parse_enum(complex, enclosing_namespace, names),
+            Some("array") => {
+                let items = complex
+                    .get("items")
+                    .ok_or_else(|| Error::ParseSchema("array schema has no `items`".to_string()))?;
+                Ok(Schema::Array(Box::new(Self::parse_with_namespace(
+                    items,
+                    enclosing_namespace,
+                    names,
+                )?)))
+            }
+            Some("map") => {
+                let values = complex
+                    .get("values")
+                    .ok_or_else(|| Error::ParseSchema("map schema has no `values`".to_string()))?;
+                Ok(Schema::Map(Box::new(Self::parse_with_namespace(
+                    values,
+                    enclosing_namespace,
+                    names,
+                )?)))
+            }
+            Some("fixed") => Self::parse_fixed(complex, enclosing_namespace, names),
+            Some(name) => Self::parse_named_or_primitive(name, enclosing_namespace, names),
+            // a nested `{"type": {...}}` form
+            None => match complex.get("type") {
+                Some(inner) => Self::parse_with_namespace(inner, enclosing_namespace, names),
+                None => Err(Error::ParseSchema("no `type` field in schema".to_string())),
+            },
+        }
+    }
+
+    fn parse_record(
+        complex: &Map<String, JsonValue>,
+        enclosing_namespace: &Option<String>,
+        names: &mut HashMap<String, Schema>,
+    ) -> Result<Self> {
+        let name = Name::parse(complex, enclosing_namespace)?;
+        let record_namespace = name.namespace.clone().or_else(|| enclosing_namespace.clone());
+        let doc = complex.get("doc").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let fullname = name.fullname(&None);
+
+        // Register a placeholder before parsing fields, so a field that
+        // refers back to this record by name resolves to a `Schema::Ref`
+        // instead of erroring as an unknown type.
+        names.insert(fullname.clone(), Schema::Ref(name.clone()));
+
+        let fields_node = complex
+            .get("fields")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| Error::ParseSchema("record schema has no `fields`".to_string()))?;
+
+        let mut fields = Vec::with_capacity(fields_node.len());
+        for (position, field) in fields_node.iter().enumerate() {
+            let field = field
+                .as_object()
+                .ok_or_else(|| Error::ParseSchema("record field is not an object".to_string()))?;
+            let field_name = field
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| Error::ParseSchema("record field has no `name`".to_string()))?
+                .to_string();
+            let field_doc = field.get("doc").and_then(|v| v.as_str()).map(|s| s.to_string());
+            let field_type = field
+                .get("type")
+                .ok_or_else(|| Error::ParseSchema("record field has no `type`".to_string()))?;
+            let schema = Self::parse_with_namespace(field_type, &record_namespace, names)?;
+            let default = field.get("default").cloned();
+            fields.push(RecordField {
+                name: field_name,
+                doc: field_doc,
+                default,
+                schema,
+                position,
+            });
+        }
+
+        let lookup = fields
+            .iter()
+            .map(|field| (field.name.clone(), field.position))
+            .collect();
+
+        let schema = Schema::Record {
+            name,
+            doc,
+            fields,
+            lookup,
+        };
+        names.insert(fullname, schema.clone());
+        Ok(schema)
+    }
+
+    fn parse_enum(
+        complex: &Map<String, JsonValue>,
+        enclosing_namespace: &Option<String>,
+        names: &mut HashMap<String, Schema>,
+    ) -> Result<Self> {
+        let name = Name::parse(complex, enclosing_namespace)?;
+        let fullname = name.fullname(&None);
+        let doc = complex.get("doc").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let symbols = complex
+            .get("symbols")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| Error::ParseSchema("enum schema has no `symbols`".to_string()))?
+            .iter()
+            .map(|s| {
+                s.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| Error::ParseSchema("enum symbol is not a string".to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let default = complex.get("default").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+        let schema = Schema::Enum {
+            name,
+            doc,
+            symbols,
+            default,
+        };
+        names.insert(fullname, schema.clone());
+        Ok(schema)
+    }
+
+    fn parse_fixed(
+        complex: &Map<String, JsonValue>,
+        enclosing_namespace: &Option<String>,
+        names: &mut HashMap<String, Schema>,
+    ) -> Result<Self> {
+        let name = Name::parse(complex, enclosing_namespace)?;
+        let fullname = name.fullname(&None);
+        let size = complex
+            .get("size")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| Error::ParseSchema("fixed schema has no `size`".to_string()))?;
+        let schema = Schema::Fixed {
+            name,
+            size: usize::try_from(size).map_err(|e| Error::ParseSchema(e.to_string()))?,
+        };
+        names.insert(fullname, schema.clone());
+        Ok(schema)
+    }
+
+    /// A short string identifying the broad kind of this schema, used to
+    /// match union branches.
+    pub(crate) fn canonical_kind(&self) -> String {
+        match self {
+            Schema::Null => "null",
+            Schema::Boolean => "boolean",
+            Schema::Int => "int",
+            Schema::Long => "long",
+            Schema::Float => "float",
+            Schema::Double => "double",
+            Schema::Bytes => "bytes",
+            Schema::String => "string",
+            Schema::Array(_) => "array",
+            Schema::Map(_) => "map",
+            Schema::Union(_) => "union",
+            Schema::Record { .. } => "record",
+            Schema::Enum { .. } => "enum",
+            Schema::Fixed { .. } => "fixed",
+            Schema::Ref(_) => "ref",
+        }
+        .to_string()
+    }
+
+    /// The tag used to disambiguate this schema as a union branch in Avro's
+    /// JSON encoding: a named type's fullname, or its `canonical_kind()`
+    /// otherwise.
+    pub(crate) fn json_tag(&self) -> String {
+        match self.name() {
+            Some(name) => name.fullname(&None),
+            None => self.canonical_kind(),
+        }
+    }
+
+    pub(crate) fn name(&self) -> Option<&Name> {
+        match self {
+            Schema::Record { name, .. } | Schema::Enum { name, .. } | Schema::Fixed { name, .. } | Schema::Ref(name) => {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Computes this schema's
+    /// [Parsing Canonical Form](https://avro.apache.org/docs/current/spec.html#Parsing+Canonical+Form+for+Schemas):
+    /// a normalized JSON string with defaults, docs and aliases stripped,
+    /// attributes ordered, and names fully qualified. This is the form
+    /// fingerprinted for schema identity (e.g. by [`crate::rabin`]).
+    pub fn canonical_form(&self) -> String {
+        let mut buf = String::new();
+        self.write_canonical_form(&None, &mut buf);
+        buf
+    }
+
+    fn write_canonical_form(&self, enclosing_namespace: &Option<String>, buf: &mut String) {
+        match self {
+            Schema::Null => buf.push_str("\"null\""),
+            Schema::Boolean => buf.push_str("\"boolean\""),
+            Schema::Int => buf.push_str("\"int\""),
+            Schema::Long => buf.push_str("\"long\""),
+            Schema::Float => buf.push_str("\"float\""),
+            Schema::Double => buf.push_str("\"double\""),
+            Schema::Bytes => buf.push_str("\"bytes\""),
+            Schema::String => buf.push_str("\"string\""),
+            Schema::Array(inner) => {
+                buf.push_str("{\"type\":\"array\",\"items\":");
+                inner.write_canonical_form(enclosing_namespace, buf);
+                buf.push('}');
+            }
+            Schema::Map(inner) => {
+                buf.push_str("{\"type\":\"map\",\"values\":");
+                inner.write_canonical_form(enclosing_namespace, buf);
+                buf.push('}');
+            }
+            Schema::Union(union) => {
+                buf.push('[');
+                for (i, variant) in union.variants().iter().enumerate() {
+                    if i > 0 {
+                        buf.push(',');
+                    }
+                    variant.write_canonical_form(enclosing_namespace, buf);
+                }
+                buf.push(']');
+            }
+            Schema::Record { name, fields, .. } => {
+                let fullname = name.fullname(enclosing_namespace);
+                let field_namespace = extract_namespace(&fullname);
+                buf.push_str("{\"name\":");
+                buf.push_str(&quote(&fullname));
+                buf.push_str(",\"type\":\"record\",\"fields\":[");
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        buf.push(',');
+                    }
+                    buf.push_str("{\"name\":");
+                    buf.push_str(&quote(&field.name));
+                    buf.push_str(",\"type\":");
+                    field.schema.write_canonical_form(&field_namespace, buf);
+                    buf.push('}');
+                }
+                buf.push_str("]}");
+            }
+            Schema::Enum { name, symbols, .. } => {
+                let fullname = name.fullname(enclosing_namespace);
+                buf.push_str("{\"name\":");
+                buf.push_str(&quote(&fullname));
+                buf.push_str(",\"type\":\"enum\",\"symbols\":[");
+                for (i, symbol) in symbols.iter().enumerate() {
+                    if i > 0 {
+                        buf.push(',');
+                    }
+                    buf.push_str(&quote(symbol));
+                }
+                buf.push_str("]}");
+            }
+            Schema::Fixed { name, size } => {
+                let fullname = name.fullname(enclosing_namespace);
+                buf.push_str("{\"name\":");
+                buf.push_str(&quote(&fullname));
+                buf.push_str(",\"type\":\"fixed\",\"size\":");
+                buf.push_str(&size.to_string());
+                buf.push('}');
+            }
+            // A reference to a named type is its own canonical form: just
+            // the fullname, same as the Avro spec requires for any
+            // already-defined named type referenced again.
+            Schema::Ref(name) => buf.push_str(&quote(&name.fullname(enclosing_namespace))),
+        }
+    }
+}
+
+/// Quotes and escapes `s` as a JSON string.
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).expect("string serialization is infallible")
+}
+
+/// Recovers the namespace portion of a fullname, to serve as the enclosing
+/// namespace for a named schema's nested fields.
+fn extract_namespace(fullname: &str) -> Option<String> {
+    fullname.rfind('.').map(|idx| fullname[..idx].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Value;
+
+    #[test]
+    fn record_parses_with_a_self_referential_field() {
+        let schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "LinkedNode",
+                "fields": [
+                    {"name": "value", "type": "int"},
+                    {"name": "next", "type": ["null", "LinkedNode"]}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        match &schema {
+            Schema::Record { fields, .. } => match &fields[1].schema {
+                Schema::Union(union) => {
+                    assert_eq!(union.variants()[1], Schema::Ref(Name::from_parts("LinkedNode", None).unwrap()));
+                }
+                other => panic!("expected a union schema, got {:?}", other),
+            },
+            other => panic!("expected a record schema, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn record_field_reuses_a_previously_declared_named_type() {
+        let schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "Card",
+                "fields": [
+                    {"name": "suit", "type": {"type": "enum", "name": "Suit", "symbols": ["SPADES", "HEARTS"]}},
+                    {"name": "suit2", "type": "Suit"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let fields = match &schema {
+            Schema::Record { fields, .. } => fields,
+            other => panic!("expected a record schema, got {:?}", other),
+        };
+        assert_eq!(fields[0].schema, fields[1].schema);
+        assert!(matches!(fields[1].schema, Schema::Enum { .. }));
+    }
+
+    #[test]
+    fn unknown_bare_name_still_errors() {
+        let err = Schema::parse_str(r#"{"type": "record", "name": "R", "fields": [{"name": "f", "type": "Ghost"}]}"#)
+            .unwrap_err();
+        assert!(matches!(err, Error::ParseSchema(_)));
+    }
+
+    #[test]
+    fn find_schema_prefers_exact_kind_over_promotion() {
+        let schema = Schema::parse_str(r#"["long", "int"]"#).unwrap();
+        let union = match &schema {
+            Schema::Union(union) => union,
+            _ => unreachable!(),
+        };
+
+        let (index, matched) = union.find_schema(&Value::Int(5)).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(matched, &Schema::Int);
+    }
+
+    #[test]
+    fn find_schema_falls_back_to_promotion_when_no_exact_match() {
+        let schema = Schema::parse_str(r#"["long", "string"]"#).unwrap();
+        let union = match &schema {
+            Schema::Union(union) => union,
+            _ => unreachable!(),
+        };
+
+        let (index, matched) = union.find_schema(&Value::Int(5)).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(matched, &Schema::Long);
+    }
+}
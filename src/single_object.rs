@@ -0,0 +1,176 @@
+//! The Avro [single-object encoding](https://avro.apache.org/docs/current/spec.html#single_object_encoding):
+//! a compact byte stream that identifies its schema by fingerprint instead
+//! of embedding the full schema, suitable for messages on a bus like Kafka
+//! where the schema is agreed out-of-band.
+//!
+//! The stream is a 2-byte marker `0xC3 0x01`, followed by the little-endian
+//! 8-byte [`rabin`](crate::rabin) fingerprint of the schema, followed by the
+//! value encoded exactly as [`crate::encode::encode`] would encode it.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use crate::decode::decode;
+use crate::encode::encode;
+use crate::error::{Error, Result};
+use crate::rabin::fingerprint;
+use crate::schema::Schema;
+use crate::types::{ToAvro, Value};
+
+const MARKER: [u8; 2] = [0xC3, 0x01];
+
+/// Encodes `value` as a single-object encoding message against `schema`.
+pub fn encode_single_object(schema: &Schema, value: &Value) -> Result<Vec<u8>> {
+    if !value.validate(schema) {
+        return Err(Error::Validation(Box::new(value.clone()), Box::new(schema.clone())));
+    }
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&MARKER);
+    buffer.extend_from_slice(&fingerprint(schema).to_le_bytes());
+    encode(value, schema, &mut buffer);
+    Ok(buffer)
+}
+
+/// Decodes a single-object encoding message from `bytes`, resolving its
+/// writer schema through `schemas_by_fingerprint`.
+///
+/// Returns the decoded value along with the fingerprint it was written
+/// with.
+pub fn decode_single_object(bytes: &[u8], schemas_by_fingerprint: &HashMap<u64, Schema>) -> Result<(Value, u64)> {
+    let mut reader = bytes;
+    decode_single_object_from(&mut reader, schemas_by_fingerprint)
+}
+
+fn decode_single_object_from<R: Read>(
+    reader: &mut R,
+    schemas_by_fingerprint: &HashMap<u64, Schema>,
+) -> Result<(Value, u64)> {
+    let mut marker = [0u8; 2];
+    reader.read_exact(&mut marker).map_err(Error::Io)?;
+    if marker != MARKER {
+        return Err(Error::Decode("missing single-object encoding marker".to_string()));
+    }
+
+    let mut fingerprint_bytes = [0u8; 8];
+    reader.read_exact(&mut fingerprint_bytes).map_err(Error::Io)?;
+    let fp = u64::from_le_bytes(fingerprint_bytes);
+
+    let schema = schemas_by_fingerprint
+        .get(&fp)
+        .ok_or_else(|| Error::Decode(format!("unknown schema fingerprint: {:016x}", fp)))?;
+
+    let value = decode(schema, reader)?;
+    Ok((value, fp))
+}
+
+/// Writes a stream of single-object encoding messages against a fixed
+/// `schema`, computing the fingerprint once up front.
+pub struct SingleObjectWriter<'a, W> {
+    schema: &'a Schema,
+    fingerprint: u64,
+    writer: W,
+}
+
+impl<'a, W: Write> SingleObjectWriter<'a, W> {
+    /// Creates a writer that encodes values matching `schema`.
+    pub fn new(schema: &'a Schema, writer: W) -> Self {
+        SingleObjectWriter {
+            schema,
+            fingerprint: fingerprint(schema),
+            writer,
+        }
+    }
+
+    /// Encodes and writes a single value as one single-object message.
+    pub fn write<T: ToAvro>(&mut self, value: T) -> Result<usize> {
+        let avro_value = value.avro();
+        if !avro_value.validate(self.schema) {
+            return Err(Error::Validation(Box::new(avro_value), Box::new(self.schema.clone())));
+        }
+
+        let mut buffer = Vec::with_capacity(10);
+        buffer.extend_from_slice(&MARKER);
+        buffer.extend_from_slice(&self.fingerprint.to_le_bytes());
+        encode(&avro_value, self.schema, &mut buffer);
+
+        self.writer.write_all(&buffer).map_err(Error::Io)?;
+        Ok(buffer.len())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads a stream of single-object encoding messages, resolving each
+/// message's writer schema by its embedded fingerprint.
+pub struct SingleObjectReader<'a, R> {
+    schemas_by_fingerprint: &'a HashMap<u64, Schema>,
+    reader: R,
+}
+
+impl<'a, R: Read> SingleObjectReader<'a, R> {
+    /// Creates a reader that resolves fingerprints against
+    /// `schemas_by_fingerprint`.
+    pub fn new(schemas_by_fingerprint: &'a HashMap<u64, Schema>, reader: R) -> Self {
+        SingleObjectReader {
+            schemas_by_fingerprint,
+            reader,
+        }
+    }
+
+    /// Reads and decodes the next single-object message.
+    pub fn read(&mut self) -> Result<Value> {
+        decode_single_object_from(&mut self.reader, self.schemas_by_fingerprint).map(|(value, _)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_encode_and_decode() {
+        let schema = Schema::parse_str(r#"{"type": "string"}"#).unwrap();
+        let value = "hello".avro();
+
+        let bytes = encode_single_object(&schema, &value).unwrap();
+        assert_eq!(&bytes[..2], &MARKER);
+
+        let mut schemas = HashMap::new();
+        schemas.insert(fingerprint(&schema), schema);
+
+        let (decoded, fp) = decode_single_object(&bytes, &schemas).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(fp, *schemas.keys().next().unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_fingerprint() {
+        let schema = Schema::parse_str(r#"{"type": "string"}"#).unwrap();
+        let bytes = encode_single_object(&schema, &"hi".avro()).unwrap();
+
+        let err = decode_single_object(&bytes, &HashMap::new()).unwrap_err();
+        assert!(matches!(err, Error::Decode(_)));
+    }
+
+    #[test]
+    fn writer_and_reader_round_trip_multiple_messages() {
+        let schema = Schema::parse_str(r#"{"type": "long"}"#).unwrap();
+        let mut buffer = Vec::new();
+
+        {
+            let mut writer = SingleObjectWriter::new(&schema, &mut buffer);
+            writer.write(1i64).unwrap();
+            writer.write(2i64).unwrap();
+        }
+
+        let mut schemas = HashMap::new();
+        schemas.insert(fingerprint(&schema), schema.clone());
+
+        let mut reader = SingleObjectReader::new(&schemas, buffer.as_slice());
+        assert_eq!(reader.read().unwrap(), Value::Long(1));
+        assert_eq!(reader.read().unwrap(), Value::Long(2));
+    }
+}
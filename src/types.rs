@@ -0,0 +1,453 @@
+//! Logic for parsing and interacting with values written against an Avro schema.
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::schema::{Schema, UnionSchema};
+
+/// A valid Avro value.
+///
+/// Values produced by [`Record`] or by hand should be validated against a
+/// [`Schema`](crate::schema::Schema) before being encoded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Boolean(bool),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Bytes(Vec<u8>),
+    String(String),
+    Fixed(usize, Vec<u8>),
+    Enum(i32, String),
+    Union(Box<Value>),
+    Array(Vec<Value>),
+    Map(HashMap<String, Value>),
+    Record(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn canonical_kind(&self) -> String {
+        match self {
+            Value::Null => "null",
+            Value::Boolean(_) => "boolean",
+            Value::Int(_) => "int",
+            Value::Long(_) => "long",
+            Value::Float(_) => "float",
+            Value::Double(_) => "double",
+            Value::Bytes(_) => "bytes",
+            Value::String(_) => "string",
+            Value::Fixed(_, _) => "fixed",
+            Value::Enum(_, _) => "enum",
+            Value::Union(_) => "union",
+            Value::Array(_) => "array",
+            Value::Map(_) => "map",
+            Value::Record(_) => "record",
+        }
+        .to_string()
+    }
+
+    /// Validates that this value conforms to `schema`.
+    pub fn validate(&self, schema: &Schema) -> bool {
+        match (self, schema) {
+            (Value::Null, Schema::Null) => true,
+            (Value::Boolean(_), Schema::Boolean) => true,
+            (Value::Int(_), Schema::Int) => true,
+            (Value::Long(_), Schema::Long) => true,
+            (Value::Int(_), Schema::Long) => true,
+            (Value::Float(_), Schema::Float) => true,
+            (Value::Float(_), Schema::Double) => true,
+            (Value::Double(_), Schema::Double) => true,
+            (Value::Bytes(_), Schema::Bytes) => true,
+            (Value::String(_), Schema::String) => true,
+            // Avro allows bytes <-> string promotion; the serializer in
+            // `crate::ser` always produces one or the other from the Rust
+            // type alone, so this lets e.g. a `String` field serialize
+            // against a `"bytes"` schema without a schema-aware serializer.
+            (Value::String(_), Schema::Bytes) => true,
+            (Value::Bytes(_), Schema::String) => true,
+            (Value::Fixed(n, _), Schema::Fixed { size, .. }) => n == size,
+            (Value::String(s), Schema::Enum { symbols, .. }) => symbols.contains(s),
+            (Value::Enum(i, s), Schema::Enum { symbols, .. }) => {
+                symbols.get(*i as usize).map(|sym| sym == s).unwrap_or(false)
+            }
+            (Value::Union(inner), Schema::Union(union)) => union
+                .variants()
+                .iter()
+                .any(|variant_schema| inner.validate(variant_schema)),
+            (v, Schema::Union(union)) => union.variants().iter().any(|variant_schema| v.validate(variant_schema)),
+            (Value::Array(items), Schema::Array(inner)) => items.iter().all(|item| item.validate(inner)),
+            (Value::Map(items), Schema::Map(inner)) => items.values().all(|item| item.validate(inner)),
+            (Value::Record(fields), Schema::Record { fields: schema_fields, .. }) => {
+                fields.len() == schema_fields.len()
+                    && fields
+                        .iter()
+                        .zip(schema_fields.iter())
+                        .all(|((name, value), field)| name == &field.name && value.validate(&field.schema))
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts this value to its Avro JSON encoding, per `schema`.
+    ///
+    /// Bytes and fixed values become a string of `\u00NN`-escaped code
+    /// points, unions become `{"<branch tag>": value}` objects (`null` is
+    /// written bare), and records/maps become JSON objects. A union branch's
+    /// tag is its fullname for a named type (record/enum/fixed), or its
+    /// `canonical_kind()` otherwise — the same disambiguation `from_json`
+    /// expects back.
+    pub fn to_json(&self, schema: &Schema) -> serde_json::Value {
+        use serde_json::Value as Json;
+
+        if let Schema::Union(union) = schema {
+            return match self {
+                Value::Union(inner) => match inner.as_ref() {
+                    Value::Null => Json::Null,
+                    other => union_branch_json(other, union),
+                },
+                Value::Null => Json::Null,
+                other => union_branch_json(other, union),
+            };
+        }
+
+        match (self, schema) {
+            (Value::Null, _) => Json::Null,
+            (Value::Boolean(b), _) => Json::Bool(*b),
+            (Value::Int(i), _) => Json::from(*i),
+            (Value::Long(i), _) => Json::from(*i),
+            (Value::Float(f), _) => serde_json::Number::from_f64(f64::from(*f))
+                .map(Json::Number)
+                .unwrap_or(Json::Null),
+            (Value::Double(f), _) => serde_json::Number::from_f64(*f).map(Json::Number).unwrap_or(Json::Null),
+            (Value::Bytes(bytes), _) | (Value::Fixed(_, bytes), _) => Json::String(bytes_to_code_points(bytes)),
+            (Value::String(s), _) => Json::String(s.clone()),
+            (Value::Enum(_, symbol), _) => Json::String(symbol.clone()),
+            (Value::Array(items), Schema::Array(inner)) => {
+                Json::Array(items.iter().map(|item| item.to_json(inner)).collect())
+            }
+            (Value::Map(map), Schema::Map(inner)) => {
+                Json::Object(map.iter().map(|(k, v)| (k.clone(), v.to_json(inner))).collect())
+            }
+            (Value::Record(fields), Schema::Record { fields: schema_fields, .. }) => Json::Object(
+                fields
+                    .iter()
+                    .zip(schema_fields.iter())
+                    .map(|((k, v), field)| (k.clone(), v.to_json(&field.schema)))
+                    .collect(),
+            ),
+            (value, _) => value.to_json_untyped(),
+        }
+    }
+
+    /// Best-effort JSON conversion for a value whose schema is unknown (or
+    /// doesn't match), used only as a fallback when `to_json`'s `(self,
+    /// schema)` match can't pair up a variant. Named-type union branches
+    /// tagged this way collapse to their `canonical_kind()`, same as before
+    /// this was schema-aware.
+    fn to_json_untyped(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+
+        match self {
+            Value::Null => Json::Null,
+            Value::Boolean(b) => Json::Bool(*b),
+            Value::Int(i) => Json::from(*i),
+            Value::Long(i) => Json::from(*i),
+            Value::Float(f) => serde_json::Number::from_f64(f64::from(*f))
+                .map(Json::Number)
+                .unwrap_or(Json::Null),
+            Value::Double(f) => serde_json::Number::from_f64(*f).map(Json::Number).unwrap_or(Json::Null),
+            Value::Bytes(bytes) | Value::Fixed(_, bytes) => Json::String(bytes_to_code_points(bytes)),
+            Value::String(s) => Json::String(s.clone()),
+            Value::Enum(_, symbol) => Json::String(symbol.clone()),
+            Value::Array(items) => Json::Array(items.iter().map(Value::to_json_untyped).collect()),
+            Value::Map(map) => Json::Object(map.iter().map(|(k, v)| (k.clone(), v.to_json_untyped())).collect()),
+            Value::Record(fields) => {
+                Json::Object(fields.iter().map(|(k, v)| (k.clone(), v.to_json_untyped())).collect())
+            }
+            Value::Union(inner) => match inner.as_ref() {
+                Value::Null => Json::Null,
+                other => {
+                    let mut map = serde_json::Map::with_capacity(1);
+                    map.insert(other.canonical_kind(), other.to_json_untyped());
+                    Json::Object(map)
+                }
+            },
+        }
+    }
+
+    /// Parses `json` into a `Value` matching `schema`, which disambiguates
+    /// e.g. `int` vs `long`, `bytes` vs `string`, and which union branch a
+    /// tagged `{"<branch type>": value}` object belongs to.
+    pub fn from_json(json: &serde_json::Value, schema: &Schema) -> Result<Value> {
+        use serde_json::Value as Json;
+
+        match (schema, json) {
+            (Schema::Null, Json::Null) => Ok(Value::Null),
+            (Schema::Boolean, Json::Bool(b)) => Ok(Value::Boolean(*b)),
+            (Schema::Int, Json::Number(n)) => n
+                .as_i64()
+                .map(|n| Value::Int(n as i32))
+                .ok_or_else(|| Error::Decode(format!("invalid int: {}", n))),
+            (Schema::Long, Json::Number(n)) => n
+                .as_i64()
+                .map(Value::Long)
+                .ok_or_else(|| Error::Decode(format!("invalid long: {}", n))),
+            (Schema::Float, Json::Number(n)) => n
+                .as_f64()
+                .map(|n| Value::Float(n as f32))
+                .ok_or_else(|| Error::Decode(format!("invalid float: {}", n))),
+            (Schema::Double, Json::Number(n)) => n
+                .as_f64()
+                .map(Value::Double)
+                .ok_or_else(|| Error::Decode(format!("invalid double: {}", n))),
+            (Schema::String, Json::String(s)) => Ok(Value::String(s.clone())),
+            (Schema::Bytes, Json::String(s)) => Ok(Value::Bytes(code_points_to_bytes(s)?)),
+            (Schema::Fixed { size, .. }, Json::String(s)) => {
+                let bytes = code_points_to_bytes(s)?;
+                if bytes.len() != *size {
+                    return Err(Error::Decode(format!(
+                        "expected {} bytes for fixed, got {}",
+                        size,
+                        bytes.len()
+                    )));
+                }
+                Ok(Value::Fixed(*size, bytes))
+            }
+            (Schema::Enum { symbols, .. }, Json::String(s)) => symbols
+                .iter()
+                .position(|symbol| symbol == s)
+                .map(|index| Value::Enum(index as i32, s.clone()))
+                .ok_or_else(|| Error::Decode(format!("unknown enum symbol: {}", s))),
+            (Schema::Array(inner), Json::Array(items)) => Ok(Value::Array(
+                items
+                    .iter()
+                    .map(|item| Value::from_json(item, inner))
+                    .collect::<Result<_>>()?,
+            )),
+            (Schema::Map(inner), Json::Object(map)) => {
+                let mut out = HashMap::with_capacity(map.len());
+                for (key, value) in map {
+                    out.insert(key.clone(), Value::from_json(value, inner)?);
+                }
+                Ok(Value::Map(out))
+            }
+            (Schema::Record { fields, .. }, Json::Object(map)) => {
+                let mut out = Vec::with_capacity(fields.len());
+                for field in fields {
+                    let value = map
+                        .get(&field.name)
+                        .ok_or_else(|| Error::Decode(format!("missing field `{}`", field.name)))?;
+                    out.push((field.name.clone(), Value::from_json(value, &field.schema)?));
+                }
+                Ok(Value::Record(out))
+            }
+            (Schema::Union(union), Json::Null) if union.is_nullable() => Ok(Value::Union(Box::new(Value::Null))),
+            (Schema::Union(union), Json::Object(map)) if map.len() == 1 => {
+                let (branch_name, branch_json) = map.iter().next().expect("checked len == 1");
+                let branch = union
+                    .variants()
+                    .iter()
+                    .find(|variant| &variant.json_tag() == branch_name)
+                    .ok_or_else(|| Error::Decode(format!("no union branch named `{}`", branch_name)))?;
+                Ok(Value::Union(Box::new(Value::from_json(branch_json, branch)?)))
+            }
+            (Schema::Union(union), json) => union
+                .variants()
+                .iter()
+                .find_map(|variant| Value::from_json(json, variant).ok())
+                .map(|value| Value::Union(Box::new(value)))
+                .ok_or_else(|| Error::Decode(format!("no union branch matches json value {}", json))),
+            (schema, json) => Err(Error::Decode(format!(
+                "cannot build a value of schema {:?} from json {}",
+                schema, json
+            ))),
+        }
+    }
+}
+
+/// Tags `value` with the fullname (or `canonical_kind()`) of whichever
+/// variant of `union` it structurally matches, as Avro's JSON encoding
+/// requires for union branches.
+fn union_branch_json(value: &Value, union: &UnionSchema) -> serde_json::Value {
+    match union.find_schema(value) {
+        Some((_, variant_schema)) => {
+            let mut map = serde_json::Map::with_capacity(1);
+            map.insert(variant_schema.json_tag(), value.to_json(variant_schema));
+            serde_json::Value::Object(map)
+        }
+        None => serde_json::Value::Null,
+    }
+}
+
+fn bytes_to_code_points(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+pub(crate) fn code_points_to_bytes(s: &str) -> Result<Vec<u8>> {
+    s.chars()
+        .map(|c| {
+            let code_point = c as u32;
+            if code_point > 0xff {
+                Err(Error::Decode(format!("invalid byte code point: U+{:04X}", code_point)))
+            } else {
+                Ok(code_point as u8)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bytes_round_trip_through_code_points() {
+        let schema = Schema::Bytes;
+        let value = Value::Bytes(vec![0, 1, 255, 128]);
+        let json = value.to_json(&schema);
+        assert_eq!(Value::from_json(&json, &schema).unwrap(), value);
+    }
+
+    #[test]
+    fn nullable_union_round_trips_bare_null_and_tagged_value() {
+        let schema = Schema::parse_str(r#"["null", "string"]"#).unwrap();
+
+        let null_value = Value::Union(Box::new(Value::Null));
+        assert_eq!(null_value.to_json(&schema), serde_json::Value::Null);
+        assert_eq!(Value::from_json(&serde_json::Value::Null, &schema).unwrap(), null_value);
+
+        let string_value = Value::Union(Box::new(Value::String("hi".to_string())));
+        let json = string_value.to_json(&schema);
+        assert_eq!(json, json!({"string": "hi"}));
+        assert_eq!(Value::from_json(&json, &schema).unwrap(), string_value);
+    }
+
+    #[test]
+    fn union_of_two_named_records_tags_by_fullname_not_canonical_kind() {
+        let schema = Schema::parse_str(
+            r#"[
+                {"type": "record", "name": "Dog", "fields": [{"name": "bark", "type": "string"}]},
+                {"type": "record", "name": "Cat", "fields": [{"name": "meow", "type": "string"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let dog = Value::Union(Box::new(Value::Record(vec![("bark".to_string(), Value::String("woof".to_string()))])));
+        let cat = Value::Union(Box::new(Value::Record(vec![("meow".to_string(), Value::String("mrow".to_string()))])));
+
+        let dog_json = dog.to_json(&schema);
+        let cat_json = cat.to_json(&schema);
+        assert_ne!(dog_json, cat_json, "two distinct record branches must not share a JSON tag");
+        assert_eq!(dog_json, json!({"Dog": {"bark": "woof"}}));
+        assert_eq!(cat_json, json!({"Cat": {"meow": "mrow"}}));
+
+        assert_eq!(Value::from_json(&dog_json, &schema).unwrap(), dog);
+        assert_eq!(Value::from_json(&cat_json, &schema).unwrap(), cat);
+    }
+}
+
+/// Any type that can be turned into an Avro [`Value`].
+pub trait ToAvro {
+    fn avro(self) -> Value;
+}
+
+macro_rules! to_avro_impl {
+    ($t:ty, $v:expr) => {
+        impl ToAvro for $t {
+            fn avro(self) -> Value {
+                $v(self)
+            }
+        }
+    };
+}
+
+to_avro_impl!(bool, Value::Boolean);
+to_avro_impl!(i32, Value::Int);
+to_avro_impl!(i64, Value::Long);
+to_avro_impl!(f32, Value::Float);
+to_avro_impl!(f64, Value::Double);
+to_avro_impl!(String, Value::String);
+to_avro_impl!(Vec<u8>, Value::Bytes);
+
+impl ToAvro for () {
+    fn avro(self) -> Value {
+        Value::Null
+    }
+}
+
+impl ToAvro for &str {
+    fn avro(self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToAvro for &[u8] {
+    fn avro(self) -> Value {
+        Value::Bytes(self.to_vec())
+    }
+}
+
+impl<T> ToAvro for Option<T>
+where
+    T: ToAvro,
+{
+    fn avro(self) -> Value {
+        Value::Union(Box::new(match self {
+            Some(v) => v.avro(),
+            None => Value::Null,
+        }))
+    }
+}
+
+impl ToAvro for Value {
+    fn avro(self) -> Value {
+        self
+    }
+}
+
+/// A convenience builder for [`Value::Record`] that looks up field positions
+/// from the schema so fields can be put in any order.
+pub struct Record<'a> {
+    pub fields: Vec<(String, Value)>,
+    schema_lookup: &'a HashMap<String, usize>,
+}
+
+impl<'a> Record<'a> {
+    /// Creates a new, empty `Record` for the given (record) `schema`.
+    ///
+    /// Returns `None` if `schema` is not a `Schema::Record`.
+    pub fn new(schema: &'a Schema) -> Option<Record<'a>> {
+        match schema {
+            Schema::Record { fields, lookup, .. } => {
+                let default_fields = fields
+                    .iter()
+                    .map(|field| (field.name.clone(), Value::Null))
+                    .collect();
+                Some(Record {
+                    fields: default_fields,
+                    schema_lookup: lookup,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Puts `value` into the field named `field`, if it exists in the schema.
+    pub fn put<V>(&mut self, field: &str, value: V)
+    where
+        V: ToAvro,
+    {
+        if let Some(&position) = self.schema_lookup.get(field) {
+            self.fields[position] = (field.to_string(), value.avro());
+        }
+    }
+}
+
+impl<'a> ToAvro for Record<'a> {
+    fn avro(self) -> Value {
+        Value::Record(self.fields)
+    }
+}
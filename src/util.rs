@@ -0,0 +1,73 @@
+//! Zig-zag variable-length integer encoding shared by the encoder and decoder.
+
+use std::io::Read;
+
+use crate::error::{Error, Result};
+
+pub(crate) fn zig_zag_encode(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+pub(crate) fn zig_zag_decode(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+pub(crate) fn encode_variable(mut n: u64, buffer: &mut Vec<u8>) {
+    loop {
+        if n & !0x7f == 0 {
+            buffer.push(n as u8);
+            break;
+        } else {
+            buffer.push((n & 0x7f) as u8 | 0x80);
+            n >>= 7;
+        }
+    }
+}
+
+pub(crate) fn decode_variable<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut n = 0u64;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte).map_err(Error::Io)?;
+        let byte = byte[0];
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift > 63 {
+            return Err(Error::Decode("variable length integer overflow".to_string()));
+        }
+    }
+    Ok(n)
+}
+
+pub(crate) fn zag_i64<R: Read>(reader: &mut R) -> Result<i64> {
+    decode_variable(reader).map(zig_zag_decode)
+}
+
+/// A sanity bound on any single length/count prefix read off the wire before
+/// it's used to size an allocation.
+///
+/// Without this, a handful of crafted bytes decoding to e.g. `i64::MAX` would
+/// make `vec![0u8; len]` try to allocate exabytes and abort the process
+/// instead of returning a catchable error — any consumer decoding untrusted
+/// bytes (a `.avro` file, a Kafka message via [`crate::single_object`]) could
+/// be crashed by a handful of bytes.
+const MAX_ALLOC_LEN: i64 = 512 * 1024 * 1024;
+
+/// Checks a length or count read off the wire before it's used to size an
+/// allocation, rejecting negative values and anything past [`MAX_ALLOC_LEN`].
+pub(crate) fn checked_len(len: i64, what: &str) -> Result<usize> {
+    if len < 0 {
+        Err(Error::Decode(format!("negative {} length: {}", what, len)))
+    } else if len > MAX_ALLOC_LEN {
+        Err(Error::Decode(format!(
+            "{} length {} exceeds the sanity bound of {} bytes",
+            what, len, MAX_ALLOC_LEN
+        )))
+    } else {
+        Ok(len as usize)
+    }
+}